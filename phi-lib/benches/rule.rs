@@ -0,0 +1,29 @@
+use std::{hint::black_box, time::Instant};
+
+use phi_lib::Rule;
+
+const ITERATIONS: usize = 100_000;
+
+fn bench(name: &str, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:<24} {:>10.1?}/iter",
+        elapsed / u32::try_from(ITERATIONS).unwrap()
+    );
+}
+
+/// Run with and without `--features cache` to compare.
+fn from_array() {
+    let values = [3, 2, 2, 1];
+    bench("from_array", || {
+        black_box(Rule::from_array(black_box(values)).unwrap());
+    });
+}
+
+fn main() {
+    from_array();
+}
@@ -0,0 +1,118 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use phi_lib::{Rule, Tape};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const ITERATIONS: usize = 100_000;
+
+fn bench(name: &str, mut f: impl FnMut()) {
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+    println!(
+        "{name:<24} {:>10.1?}/iter {:>10} allocations",
+        elapsed / u32::try_from(ITERATIONS).unwrap(),
+        allocations
+    );
+}
+
+fn long_tape() -> Tape {
+    let digits: Vec<_> = (0..1000).map(|i| i % 2).collect();
+    Tape::from_arrays(&digits, &digits)
+}
+
+fn clone_from() {
+    let tape = long_tape();
+    bench("clone", || {
+        let scratch = black_box(&tape).clone();
+        black_box(scratch);
+    });
+    let mut scratch = Tape::zero();
+    bench("clone_from", || {
+        scratch.clone_from(black_box(&tape));
+        black_box(&scratch);
+    });
+}
+
+/// Applies the golden ratio rule at a sweep of indices on a tape with digits large enough to never
+/// run out.
+fn apply_loop(mut apply: impl FnMut(Tape, isize) -> Tape) -> impl FnMut() {
+    let mut tape = Tape::from_arrays([1_000_000; 64], []);
+    let mut index = 2;
+    move || {
+        tape = apply(std::mem::replace(&mut tape, Tape::zero()), black_box(index));
+        index = if index == 63 { 2 } else { index + 1 };
+    }
+}
+
+fn apply_with_slice() {
+    let rule = Rule::golden();
+    bench(
+        "apply_in_place",
+        apply_loop(|tape, index| tape.apply_in_place(&rule, index).unwrap()),
+    );
+    let rule_values = rule.values();
+    bench(
+        "apply_with_slice",
+        apply_loop(|tape, index| tape.apply_with_slice(rule_values, index).unwrap()),
+    );
+}
+
+/// Adding zero should neither allocate nor touch the storage of the accumulator.
+fn add_zero() {
+    let mut sum = Tape::from_arrays([1], []);
+    bench("add_assign zero", || {
+        sum += black_box(Tape::zero());
+    });
+    let mut wide_zeros = vec![Tape::from_arrays([0; 16], [0; 16]); ITERATIONS];
+    bench("add_assign wide zero", || {
+        sum += black_box(wide_zeros.pop().unwrap());
+    });
+    assert_eq!(sum.range(), (0, 1));
+}
+
+/// Checking a long valid tape has to scan every digit, compared to the same scan through `iter`.
+fn is_valid() {
+    let rule = Rule::golden();
+    let tape = Tape::from_arrays([1; 5_000], [1; 5_000]);
+    bench("is_valid iter", || {
+        let max_allowed = black_box(&rule).first();
+        assert!(black_box(&tape).iter().all(|value| value <= max_allowed));
+    });
+    bench("is_valid", || {
+        assert!(black_box(&tape).is_valid(black_box(&rule)));
+    });
+}
+
+fn main() {
+    clone_from();
+    apply_with_slice();
+    add_zero();
+    is_valid();
+}
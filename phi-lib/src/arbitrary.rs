@@ -0,0 +1,70 @@
+//! [`Arbitrary`] implementations for use in downstream proptests.
+
+use proptest::{arbitrary::Arbitrary, collection::vec, prelude::*, strategy::BoxedStrategy};
+
+use crate::{Rule, Tape, Value};
+
+/// Largest rule value generated for an arbitrary [`Rule`].
+const MAX_RULE_VALUE: Value = 10;
+/// Largest rule length generated for an arbitrary [`Rule`].
+const MAX_RULE_LEN: usize = 6;
+/// Largest digit generated for an arbitrary [`Tape`] when no rule is given.
+const MAX_DIGIT: Value = 10;
+/// Largest number of digits generated on each side of the comma for an arbitrary [`Tape`].
+const MAX_TAPE_LEN: usize = 10;
+
+impl Arbitrary for Rule {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Rule>;
+
+    /// Rules with up to `MAX_RULE_LEN` non-increasing values between 1 and `MAX_RULE_VALUE`.
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        vec(1..=MAX_RULE_VALUE, 1..=MAX_RULE_LEN)
+            .prop_map(|mut values| {
+                values.sort_unstable_by(|a, b| b.cmp(a));
+                Rule::from_array(values).unwrap()
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Tape {
+    /// If a rule is given, the digits are at most its first value, so the tape is valid for it.
+    type Parameters = Option<Rule>;
+    type Strategy = BoxedStrategy<Tape>;
+
+    fn arbitrary_with(rule: Option<Rule>) -> Self::Strategy {
+        let max_digit = rule.map_or(MAX_DIGIT, |rule| rule.first());
+        (
+            vec(0..=max_digit, 0..=MAX_TAPE_LEN),
+            vec(0..=max_digit, 0..=MAX_TAPE_LEN),
+        )
+            .prop_map(|(positives, negatives)| Tape::from_arrays(positives, negatives))
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn arbitrary_rule(rule in any::<Rule>()) {
+            prop_assert!(Rule::try_from_array(rule.values()).is_ok());
+            prop_assert!(rule.len() <= MAX_RULE_LEN);
+            prop_assert!(rule.first() <= MAX_RULE_VALUE);
+        }
+
+        #[test]
+        fn arbitrary_tape(tape in any::<Tape>()) {
+            prop_assert!(tape.iter().all(|digit| digit <= MAX_DIGIT));
+        }
+
+        #[test]
+        fn arbitrary_valid_tape((rule, tape) in any::<Rule>()
+                .prop_flat_map(|rule| (Just(rule.clone()), any_with::<Tape>(Some(rule))))) {
+            prop_assert!(tape.is_valid(&rule));
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+use crate::Value;
+
+/// A deterministic finite automaton over digits, as returned by
+/// [`crate::Rule::admissibility_automaton`].
+///
+/// States are numbered `0..num_states()` and every state is accepting, so a digit sequence is
+/// accepted exactly when each digit has a transition from the state reached so far. Digits without
+/// a transition from a state are rejected there.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Automaton {
+    initial: usize,
+    transitions: Vec<BTreeMap<Value, usize>>,
+}
+
+impl Automaton {
+    pub(crate) fn new(initial: usize, transitions: Vec<BTreeMap<Value, usize>>) -> Self {
+        assert!(initial < transitions.len());
+        assert!(transitions
+            .iter()
+            .flat_map(BTreeMap::values)
+            .all(|&state| state < transitions.len()));
+        Automaton {
+            initial,
+            transitions,
+        }
+    }
+
+    pub fn initial(&self) -> usize {
+        self.initial
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.transitions.len()
+    }
+
+    /// The transitions out of `state` as a map from digit to the next state.
+    pub fn transitions(&self, state: usize) -> &BTreeMap<Value, usize> {
+        &self.transitions[state]
+    }
+
+    /// The state after reading `digit` in `state`, or `None` if the digit is rejected.
+    pub fn step(&self, state: usize, digit: Value) -> Option<usize> {
+        self.transitions[state].get(&digit).copied()
+    }
+
+    /// The state after reading all of `digits` from the initial state, or `None` if some digit is
+    /// rejected.
+    pub fn run(&self, digits: impl IntoIterator<Item = Value>) -> Option<usize> {
+        digits
+            .into_iter()
+            .try_fold(self.initial, |state, digit| self.step(state, digit))
+    }
+
+    pub fn accepts(&self, digits: impl IntoIterator<Item = Value>) -> bool {
+        self.run(digits).is_some()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn run() {
+        let automaton = Automaton::new(
+            0,
+            vec![BTreeMap::from([(0, 0), (1, 1)]), BTreeMap::from([(0, 0)])],
+        );
+        assert_eq!(automaton.num_states(), 2);
+        assert_eq!(automaton.step(0, 1), Some(1));
+        assert_eq!(automaton.step(1, 1), None);
+        assert_eq!(automaton.run([1, 0, 1]), Some(1));
+        assert!(automaton.accepts([]));
+        assert!(!automaton.accepts([0, 1, 1]));
+        assert!(!automaton.accepts([2]));
+    }
+}
@@ -0,0 +1,96 @@
+//! Exact conversions between tapes in integer bases and [`BigInt`].
+
+use num_bigint::{BigInt, Sign};
+
+use crate::{Rule, Tape, Value};
+
+impl Tape {
+    /// The exact value of the tape in the integer base of `rule`.
+    ///
+    /// Returns `None` if the base of the rule is not an integer or the tape has a nonzero digit at
+    /// a negative index, in which case the value is not an integer.
+    pub fn value_bigint(&self, rule: &Rule) -> Option<BigInt> {
+        let base = rule.integer_base()?;
+        let (min, max) = self.range();
+        if (min..0).any(|index| self[index] != 0) {
+            return None;
+        }
+        Some(
+            (0..max)
+                .rev()
+                .fold(BigInt::ZERO, |acc, index| acc * base + self[index]),
+        )
+    }
+
+    /// The standard tape for the integer base of `rule` with value `value`.
+    ///
+    /// Returns `None` if `value` is negative or the base of the rule is not an integer of at least
+    /// 2.
+    pub fn from_bigint(value: &BigInt, rule: &Rule) -> Option<Tape> {
+        let base = rule.integer_base().filter(|&base| base > 1)?;
+        if value.sign() == Sign::Minus {
+            return None;
+        }
+        // Digits from least significant, the remainders of repeated division by the base.
+        let mut digits = Vec::new();
+        let mut remaining = value.magnitude().clone();
+        while remaining.bits() > 0 {
+            let digit = &remaining % base;
+            digits.push(Value::try_from(&digit).unwrap());
+            remaining /= base;
+        }
+        Some(Tape::from_vecs(digits, vec![]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decimal_to_hex() {
+        let decimal = Rule::integer(10);
+        let hex = Rule::integer(16);
+        let value: BigInt = "9876543210123456789098765432101234567890".parse().unwrap();
+        let tape = Tape::from_bigint(&value, &hex).unwrap();
+        assert!(tape.is_standard(&hex));
+        let hex_digits: Vec<Value> = value
+            .to_str_radix(16)
+            .chars()
+            .map(|c| c.to_digit(16).unwrap())
+            .collect();
+        assert_eq!(hex_digits.len(), 34);
+        assert_eq!(tape, Tape::from_arrays(hex_digits, []));
+        assert_eq!(tape.value_bigint(&hex), Some(value.clone()));
+
+        let decimal_tape = Tape::from_bigint(&value, &decimal).unwrap();
+        assert_eq!(decimal_tape.range(), (0, 40));
+        assert_eq!(decimal_tape.value_bigint(&decimal), Some(value));
+    }
+
+    #[test]
+    fn unsupported() {
+        let decimal = Rule::integer(10);
+        assert_eq!(
+            Tape::from_bigint(&BigInt::ZERO, &decimal),
+            Some(Tape::zero())
+        );
+        assert_eq!(Tape::zero().value_bigint(&decimal), Some(BigInt::ZERO));
+        assert_eq!(Tape::from_bigint(&BigInt::from(-1), &decimal), None);
+        assert_eq!(Tape::from_bigint(&BigInt::from(1), &Rule::golden()), None);
+        assert_eq!(Tape::from_bigint(&BigInt::from(1), &Rule::integer(1)), None);
+        assert_eq!(Tape::from_arrays([1], [1]).value_bigint(&decimal), None);
+        assert_eq!(
+            Tape::from_arrays([1], []).value_bigint(&Rule::golden()),
+            None
+        );
+        // Non-standard digits are still summed exactly.
+        assert_eq!(
+            Tape::from_arrays([12, 15], [0]).value_bigint(&decimal),
+            Some(BigInt::from(135))
+        );
+        let big = Tape::from_arrays([Value::MAX; 3], []);
+        let expected = (0..3).fold(BigInt::ZERO, |acc, _| acc * 10 + Value::MAX);
+        assert_eq!(big.value_bigint(&decimal), Some(expected));
+    }
+}
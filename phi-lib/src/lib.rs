@@ -1,7 +1,21 @@
+#[cfg(feature = "proptest")]
+mod arbitrary;
+mod automaton;
+#[cfg(feature = "bigint")]
+mod bigint;
+mod neg_rule;
 mod rule;
 mod tape;
 
-pub use rule::Rule;
-pub use tape::Tape;
+pub use automaton::Automaton;
+pub use neg_rule::NegRule;
+pub use rule::{normalize, ParseRuleError, Rule, RuleError};
+pub use tape::{
+    value_from_digits, ApplyRuleError, DegreeError, IndexOverflow, StandardizeCache, Tape,
+    TapeBuilder, OPTIMAL_STANDARDIZE_MAX_STATES,
+};
 
-type Value = u32;
+/// A single digit of a [`Tape`] or value of a [`Rule`].
+pub type Value = u32;
+/// Signed counterpart of [`Value`] for intermediate results that may go negative.
+pub type SignedValue = i64;
@@ -0,0 +1,186 @@
+use crate::{tape::GREEDY_TOLERANCE, Rule, Tape, Value};
+
+/// A rule used with the negative base `-beta`, where `beta` is the base of the wrapped [`Rule`].
+///
+/// The digit at index `i` is worth `digit * (-beta)^i`, so every real number, including negative
+/// ones, has an expansion with digits between 0 and the first rule value. Standard forms are the
+/// greedy (-beta)-expansions of Ito and Sadahiro. They come from iterating the (-beta)-shift
+/// `T(x) = -beta * x - floor(-beta * x + beta / (beta + 1))` on the interval
+/// `[-beta / (beta + 1), 1 / (beta + 1))`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NegRule {
+    rule: Rule,
+}
+
+impl NegRule {
+    /// Panics if the base of `rule` is one.
+    pub fn new(rule: Rule) -> Self {
+        assert!(rule.base() > 1.);
+        NegRule { rule }
+    }
+
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    /// The negative base `-beta`.
+    pub fn base(&self) -> f64 {
+        -self.rule.base()
+    }
+
+    /// The value of `tape` in base `-beta`, i.e. `sum(digit * (-beta)^index)`.
+    ///
+    /// Panics if the populated range of the tape reaches outside the `i32` range, where the powers
+    /// of the base can't be computed with `powi`.
+    pub fn value(&self, tape: &Tape) -> f64 {
+        let base = self.base();
+        tape.fold_digits(0., |acc, index, value| {
+            acc + f64::from(value) * base.powi(i32::try_from(index).unwrap())
+        })
+    }
+
+    /// The greedy (-beta)-expansion of `value` with at most `max_places` digits at negative
+    /// indices.
+    ///
+    /// The expansion is computed in floating point and stops once the remaining value is
+    /// negligible relative to `value`, so that e.g. integers in integer bases don't get tails of
+    /// rounding errors.
+    pub fn from_f64(&self, value: f64, max_places: usize) -> Tape {
+        assert!(value.is_finite());
+        let beta = self.rule.base();
+        let lower = -beta / (beta + 1.);
+        let mut result = Tape::zero();
+        if value == 0. {
+            return result;
+        }
+        // Scale the value into the domain of the (-beta)-shift, whose digits then belong at the
+        // indices below `top`.
+        let mut top = 0;
+        let mut remainder = value;
+        while !(lower..lower + 1.).contains(&remainder) {
+            top += 1;
+            remainder /= self.base();
+        }
+        let tolerance = value.abs() * GREEDY_TOLERANCE;
+        let min_index = -isize::try_from(max_places).unwrap();
+        for index in (min_index..top).rev() {
+            // The remainder is the value of the digits at `index` and below, scaled by
+            // `(-beta)^-(index + 1)`.
+            if remainder.abs() * beta.powi(i32::try_from(index + 1).unwrap()) <= tolerance {
+                break;
+            }
+            let scaled = -beta * remainder;
+            let digit = (scaled - lower)
+                .floor()
+                .clamp(0., f64::from(self.rule.first()));
+            result[index] = digit as Value;
+            remainder = scaled - digit;
+        }
+        result
+    }
+
+    /// The greedy (-beta)-expansion of the value of `tape`, with at most `max_places` digits at
+    /// negative indices.
+    ///
+    /// This goes through the value of the tape in floating point, so unlike [`Tape::standardize`]
+    /// it is only exact while the digits involved are.
+    pub fn reexpand_f64(&self, tape: &Tape, max_places: usize) -> Tape {
+        self.from_f64(self.value(tape), max_places)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn value() {
+        let rule = NegRule::new(Rule::integer(2));
+        assert_eq!(rule.base(), -2.);
+        assert_eq!(rule.value(&Tape::from_arrays([1, 1, 0], [])), 2.);
+        assert_eq!(rule.value(&Tape::from_arrays([1, 1], [])), -1.);
+        assert_eq!(rule.value(&Tape::from_arrays([], [1])), -0.5);
+        let rule = NegRule::new(Rule::golden());
+        assert_relative_eq!(
+            rule.value(&Tape::from_arrays([1, 0, 1], [])),
+            Rule::golden().base().powi(2) + 1.,
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn integer_expansions() {
+        let rule = NegRule::new(Rule::integer(2));
+        let expected = [
+            (-2, Tape::from_arrays([1, 0], [])),
+            (-1, Tape::from_arrays([1, 1], [])),
+            (1, Tape::from_arrays([1], [])),
+            (2, Tape::from_arrays([1, 1, 0], [])),
+            (3, Tape::from_arrays([1, 1, 1], [])),
+            (4, Tape::from_arrays([1, 0, 0], [])),
+            (6, Tape::from_arrays([1, 1, 0, 1, 0], [])),
+        ];
+        for (value, tape) in expected {
+            assert_eq!(rule.from_f64(f64::from(value), 10), tape, "{value}");
+        }
+        let rule = NegRule::new(Rule::integer(10));
+        assert_eq!(rule.from_f64(19., 10), Tape::from_arrays([1, 9, 9], []));
+        assert_eq!(rule.from_f64(-5., 10), Tape::from_arrays([1, 5], []));
+        assert_eq!(rule.from_f64(0., 10), Tape::zero());
+        // Every integer has a finite expansion, and only the integers have expansions without
+        // fractional digits.
+        for value in -200..=200 {
+            let tape = rule.from_f64(f64::from(value), 10);
+            assert_eq!(tape.range().0, 0);
+            assert_eq!(rule.value(&tape), f64::from(value));
+        }
+    }
+
+    #[test]
+    fn reexpand_f64() {
+        let rule = NegRule::new(Rule::integer(10));
+        // 9 * -10 + 12 = -78 = 8 * -10 + 2.
+        let tape = Tape::from_arrays([9, 12], []);
+        assert_eq!(rule.reexpand_f64(&tape, 10), Tape::from_arrays([8, 2], []));
+        // 12 * -10 + 9 = -111 = 1 * -1000 + 9 * 100 + 2 * -10 + 9.
+        let tape = Tape::from_arrays([12, 9], []);
+        assert_eq!(
+            rule.reexpand_f64(&tape, 10),
+            Tape::from_arrays([1, 9, 2, 9], [])
+        );
+        let standard = Tape::from_arrays([1, 8, 2], []);
+        assert_eq!(rule.reexpand_f64(&standard, 10), standard);
+    }
+
+    #[test]
+    fn golden_expansions() {
+        // The (-phi)-expansion of the left endpoint `-1 / phi` is `1 0 0 0 ...`, so by Ito and
+        // Sadahiro's criterion an expansion never has an odd number of zeros between two ones,
+        // while consecutive ones are allowed unlike in base phi.
+        let rule = NegRule::new(Rule::golden());
+        for numerator in -100..=100 {
+            let value = f64::from(numerator) / 7.;
+            let tape = rule.from_f64(value, 60);
+            assert!(tape.is_valid(rule.rule()));
+            let ones = tape
+                .digits_be()
+                .into_iter()
+                .filter(|&(_, digit)| digit == 1);
+            assert!(
+                ones.tuple_windows()
+                    .all(|((high, _), (low, _))| (high - low) % 2 == 1),
+                "{tape}"
+            );
+            assert_relative_eq!(rule.value(&tape), value, epsilon = 1e-9);
+        }
+        // `phi^-2 = (-phi)^-2` is the excluded right endpoint of the domain of the (-phi)-shift, so
+        // its expansion is `1 - 1 / phi` rather than `0.01`.
+        assert_eq!(
+            rule.from_f64(Rule::golden().base().powi(-2), 10),
+            Tape::from_arrays([1], [1])
+        );
+    }
+}
@@ -1,8 +1,41 @@
-use std::ops::Index;
+use std::{cmp::Ordering, collections::BTreeMap, ops::Index, str::FromStr};
 
 use itertools::Itertools;
+use thiserror::Error;
 
-use crate::Value;
+use crate::{Automaton, Value};
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum RuleError {
+    #[error("A rule must have at least one value.")]
+    Empty,
+    #[error("The first value of a rule must be positive.")]
+    LeadingZero,
+    #[error("Rule values must be non-increasing, but value {next} at index {index} is greater than the preceding value {previous}.")]
+    Increasing {
+        index: usize,
+        previous: Value,
+        next: Value,
+    },
+    #[error("The base of a rule with first value {first} and degree {degree} cannot be computed reliably in floating point.")]
+    ImpreciseBase { first: Value, degree: usize },
+}
+
+/// The largest first value of a rule with more than one value. At degree two the rule polynomial
+/// is then still exact in an `f64` at `first + 1`, since `(first + 1)^2 <= 2^52`.
+const MAX_FIRST_VALUE: Value = (1 << 26) - 1;
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum ParseRuleError {
+    #[error("Invalid number '{0}'.")]
+    InvalidNumber(String),
+    #[error("Unsupported polynomial form '{0}'. Expected e.g. 'x^2 = x + 1'.")]
+    UnsupportedPolynomial(String),
+    #[error("The power {0} occurs more than once.")]
+    DuplicatePower(usize),
+    #[error(transparent)]
+    Rule(#[from] RuleError),
+}
 
 #[derive(Clone, Debug)]
 pub struct Rule {
@@ -15,31 +48,314 @@ impl Rule {
     where
         A: AsRef<[Value]>,
     {
-        let values = values.as_ref();
-        if values.iter().tuple_windows().any(|(a, b)| a < b) {
-            None
-        } else {
-            let result: Vec<_> = values.iter().copied().take_while(|&v| v != 0).collect();
-            if result.is_empty() {
-                None
-            } else {
-                let rule_base = calculate_rule_base(values);
-                Some(Rule {
-                    values: result,
-                    base: rule_base,
-                })
+        Self::try_from_array(values).ok()
+    }
+
+    /// Constructs a rule from its values, which must be non-increasing and start with a positive
+    /// value.
+    ///
+    /// Trailing zeros are trimmed, so e.g. `[2, 1, 0, 0]` gives the same rule as `[2, 1]`.
+    /// Because the values must be non-increasing, zeros can only occur at the end.
+    ///
+    /// The base is found by bisection in floating point between the first value `a1` and
+    /// `a1 + 1`, so some rules are rejected with [`RuleError::ImpreciseBase`] rather than given a
+    /// wrong base:
+    /// - Rules with more than one value and `a1 > 2^26 - 1`, whose polynomial can't be evaluated
+    ///   exactly near the base even at degree two.
+    /// - Rules for which rounding makes the polynomial appear not to change sign between `a1` and
+    ///   `a1 + 1`, or where it overflows, e.g. `[2; 38]` or `[1; 1024]`.
+    ///
+    /// Integer rules `[n]` are always accepted.
+    pub fn try_from_array<A>(values: A) -> Result<Self, RuleError>
+    where
+        A: AsRef<[Value]>,
+    {
+        Self::try_from_iter(values.as_ref().iter().copied())
+    }
+
+    /// Like [`Rule::from_array`], but consumes the values directly from an iterator.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_iter(values: impl IntoIterator<Item = Value>) -> Option<Self> {
+        Self::try_from_iter(values).ok()
+    }
+
+    fn try_from_iter(values: impl IntoIterator<Item = Value>) -> Result<Self, RuleError> {
+        let mut values = values.into_iter();
+        let mut previous = match values.next() {
+            None => return Err(RuleError::Empty),
+            Some(0) => return Err(RuleError::LeadingZero),
+            Some(first) => first,
+        };
+        let mut result = vec![previous];
+        for (index, next) in values.enumerate() {
+            if previous < next {
+                return Err(RuleError::Increasing {
+                    index: index + 1,
+                    previous,
+                    next,
+                });
             }
+            if next != 0 {
+                result.push(next);
+            }
+            previous = next;
         }
+        let rule_base = if result.len() > 1 && result[0] > MAX_FIRST_VALUE {
+            None
+        } else {
+            cached_rule_base(&result)
+        };
+        let Some(rule_base) = rule_base else {
+            return Err(RuleError::ImpreciseBase {
+                first: result[0],
+                degree: result.len(),
+            });
+        };
+        Ok(Rule {
+            values: result,
+            base: rule_base,
+        })
+    }
+
+    /// The rule `[1, 1]` with the golden ratio phi = (1 + sqrt(5)) / 2 as base.
+    pub fn golden() -> Self {
+        Self::from_array([1, 1]).unwrap()
+    }
+
+    /// The rule `[n]` with the integer `n` as base.
+    ///
+    /// Panics if `n` is zero.
+    pub fn integer(n: Value) -> Self {
+        Self::from_array([n]).expect("The base of an integer rule must be positive.")
+    }
+
+    /// The rule `[1, 1, 1]` with the tribonacci constant (about 1.839) as base.
+    pub fn tribonacci() -> Self {
+        Self::from_array([1, 1, 1]).unwrap()
+    }
+
+    /// The rule `[1; order]`, whose base is the `order`-bonacci constant, e.g. phi for order 2.
+    ///
+    /// Panics if `order` is zero or above 1023, where the rule polynomial overflows at 2, see
+    /// [`Rule::try_from_array`].
+    pub fn multinacci(order: usize) -> Self {
+        Self::from_array(vec![1; order])
+            .expect("The order of a multinacci rule must be between 1 and 1023.")
+    }
+
+    /// `Some(order)` if the rule is `[1; order]` and `None` otherwise.
+    pub fn multinacci_order(&self) -> Option<usize> {
+        self.iter().all(|value| value == 1).then_some(self.len())
+    }
+
+    /// All rules with exactly `degree` values, each between 1 and `max_digit`.
+    pub fn enumerate_degree(degree: usize, max_digit: Value) -> impl Iterator<Item = Rule> {
+        (1..=max_digit)
+            .combinations_with_replacement(degree)
+            .filter_map(|mut values| {
+                values.reverse();
+                Rule::from_array(values)
+            })
+    }
+
+    /// The rule with the first value replaced by `new_first`, if the values are still
+    /// non-increasing.
+    pub fn with_first_digit(&self, new_first: Value) -> Option<Rule> {
+        let mut values = self.values.clone();
+        values[0] = new_first;
+        Rule::from_array(values)
     }
 
     pub fn first(&self) -> Value {
         self.values.first().copied().unwrap()
     }
 
+    /// Checks that every digit in `digits` is at most the first rule value, the largest digit of a
+    /// valid tape, returning the position of the first digit that isn't.
+    pub fn check_digits(&self, digits: &[Value]) -> Result<(), usize> {
+        let max_allowed = self.first();
+        match digits.iter().position(|&digit| digit > max_allowed) {
+            Some(position) => Err(position),
+            None => Ok(()),
+        }
+    }
+
+    /// The number of bits needed to store any digit of a valid tape, i.e. `ceil(log2(first + 1))`.
+    pub fn bits_per_digit(&self) -> u32 {
+        Value::BITS - self.first().leading_zeros()
+    }
+
+    /// The sum `S` of the rule values.
+    ///
+    /// The base satisfies `first <= base <= S`: dividing `base^d = sum(values[k] * base^(d - k - 1))`
+    /// by `base^(d - 1) >= 1` gives a sum of terms that are each at most the corresponding value.
+    /// Applying the rule lowers the digit sum of a tape by `S - 1`.
+    pub fn value_sum(&self) -> u64 {
+        self.iter().map(u64::from).sum()
+    }
+
     pub fn base(&self) -> f64 {
         self.base
     }
 
+    /// Formats the base with `precision` decimal places.
+    ///
+    /// The base is stored as an `f64`, so only the first 15 or so significant digits are reliable.
+    pub fn base_string(&self, precision: usize) -> String {
+        format!("{:.*}", precision, self.base)
+    }
+
+    /// The first `terms` partial quotients of the continued fraction of the base, fewer if the
+    /// continued fraction terminates.
+    ///
+    /// The computation is done in `f64`, where the error grows with every term, so only the first
+    /// 10 to 20 terms are reliable depending on the base.
+    pub fn base_continued_fraction(&self, terms: usize) -> Vec<u64> {
+        let mut result = Vec::with_capacity(terms);
+        let mut x = self.base;
+        while result.len() < terms {
+            let a = x.floor();
+            result.push(a as u64);
+            let fraction = x - a;
+            if fraction == 0. {
+                break;
+            }
+            x = 1. / fraction;
+        }
+        result
+    }
+
+    /// Returns the best rational approximation `(numerator, denominator)` of the base with a
+    /// denominator of at most `max_denom`, computed from the continued fraction of the base.
+    ///
+    /// If a numerator would overflow `u64`, the last approximation that fits is returned instead.
+    /// Panics if `max_denom` is zero.
+    pub fn base_rational_approx(&self, max_denom: u64) -> (u64, u64) {
+        assert!(max_denom > 0, "The denominator bound must be positive.");
+        let (mut p0, mut q0, mut p1, mut q1) = (0u64, 1u64, 1u64, 0u64);
+        let mut x = self.base;
+        loop {
+            let a = x.floor();
+            // The float-to-int conversion saturates, which is fine since the denominator check
+            // below catches any huge partial quotient.
+            let a_int = a as u64;
+            let q2 = a_int.saturating_mul(q1).saturating_add(q0);
+            if q2 > max_denom {
+                let k = (max_denom - q0) / q1;
+                let semi = k
+                    .checked_mul(p1)
+                    .and_then(|p| p.checked_add(p0))
+                    .map(|p| (p, k * q1 + q0));
+                let error = |p: u64, q: u64| (p as f64 / q as f64 - self.base).abs();
+                return match semi {
+                    Some((p_semi, q_semi)) if k > 0 && error(p_semi, q_semi) < error(p1, q1) => {
+                        (p_semi, q_semi)
+                    }
+                    _ => (p1, q1),
+                };
+            }
+            // The first numerator is the integer part of the base, which always fits.
+            let Some(p2) = a_int.checked_mul(p1).and_then(|p| p.checked_add(p0)) else {
+                return (p1, q1);
+            };
+            (p0, q0, p1, q1) = (p1, q1, p2, q2);
+            let fraction = x - a;
+            if fraction == 0. {
+                return (p1, q1);
+            }
+            x = 1. / fraction;
+        }
+    }
+
+    /// The final bisection bracket `(min, max, min_value, max_value)` from computing the base,
+    /// where `min_value` and `max_value` are the rule polynomial evaluated at `min` and `max`.
+    ///
+    /// The base lies between `min` and `max`, which are adjacent floats unless the polynomial
+    /// evaluates to exactly zero at the base, in which case they are equal.
+    pub fn debug_base_bracket(&self) -> (f64, f64, f64, f64) {
+        try_base_bracket(&self.values).unwrap()
+    }
+
+    /// A bound on the relative error of [`Rule::base`]: the width of the final bisection bracket,
+    /// see [`Rule::debug_base_bracket`], divided by the base.
+    ///
+    /// This assumes the signs of the rule polynomial at the ends of the bracket were computed
+    /// correctly, which holds unless the polynomial is very flat at the base, see
+    /// [`Rule::polynomial_derivative_at_base`].
+    pub fn base_error_bound(&self) -> f64 {
+        let (min, max, _, _) = self.debug_base_bracket();
+        (max - min) / self.base
+    }
+
+    /// The base if it is an integer, i.e. if the rule is a single value `n`.
+    ///
+    /// Since the [`Rule::minimal_polynomial`] is irreducible, rules of higher degree have
+    /// irrational bases, even when [`Rule::base`] happens to be within rounding of an integer.
+    pub fn integer_base(&self) -> Option<Value> {
+        (self.degree() == 1).then(|| self.first())
+    }
+
+    /// The base as an exact fraction `(numerator, denominator)` if it is rational.
+    ///
+    /// The base is an algebraic integer, so it is rational exactly when it is an integer, see
+    /// [`Rule::integer_base`].
+    pub fn base_rational(&self) -> Option<(u64, u64)> {
+        self.integer_base().map(|base| (u64::from(base), 1))
+    }
+
+    /// The number of digits at nonnegative indices in the greedy expansion of `value`, i.e.
+    /// `floor(log_base(value)) + 1`, or 0 if `value < 1`.
+    ///
+    /// The logarithm is corrected against [`f64::powi`] so exact powers of the base are counted
+    /// consistently with [`crate::Tape::from_f64`].
+    ///
+    /// Infinite values give `usize::MAX`. In base one every digit is worth one, so the integer
+    /// part has `floor(value)` digits, saturating at `usize::MAX`.
+    pub fn integer_part_length(&self, value: f64) -> usize {
+        if value.is_nan() || value < 1. {
+            return 0;
+        }
+        if value == f64::INFINITY {
+            return usize::MAX;
+        }
+        if self.base <= 1. {
+            return value.floor() as usize;
+        }
+        let mut exponent = (value.ln() / self.base.ln()).floor() as i32;
+        while self.base.powi(exponent) > value {
+            exponent -= 1;
+        }
+        while self.base.powi(exponent + 1) <= value {
+            exponent += 1;
+        }
+        usize::try_from(exponent).unwrap() + 1
+    }
+
+    /// The largest index `k` for which `base^k < 2^53`, i.e. for which a digit times `base^k`
+    /// still resolves units exactly in an `f64`.
+    ///
+    /// Tapes with digits above this index lose absolute precision in [`crate::Tape::value`], which
+    /// can guide the choice of how many places to expand.
+    pub fn max_reliable_index(&self) -> isize {
+        const LIMIT: f64 = (1u64 << f64::MANTISSA_DIGITS) as f64;
+        if self.base <= 1. {
+            return isize::MAX;
+        }
+        let mut index = (LIMIT.log2() / self.base.log2()).floor() as i32;
+        while self.base.powi(index) >= LIMIT {
+            index -= 1;
+        }
+        while self.base.powi(index + 1) < LIMIT {
+            index += 1;
+        }
+        isize::try_from(index).unwrap()
+    }
+
+    /// The degree of the polynomial defining the base, which is the number of rule values.
+    pub fn degree(&self) -> usize {
+        self.values.len()
+    }
+
     pub fn len(&self) -> usize {
         self.values.len()
     }
@@ -59,6 +375,385 @@ impl Rule {
     pub fn iter(&self) -> impl DoubleEndedIterator<Item = Value> + '_ {
         self.values.iter().copied()
     }
+
+    /// The greedy expansion of one in the rule's base.
+    ///
+    /// Since the values of a rule are non-increasing, this is exactly the rule values, so every
+    /// base representable by a `Rule` is a simple Parry number.
+    ///
+    /// By Frougny and Solomyak, a finite non-increasing expansion of one also means that every
+    /// nonnegative element of `Z[1/base]` has a finite expansion (property (F)), so this holds for
+    /// every rule. Bases without it, e.g. those with expansion of one `1 0 1`, can't be
+    /// represented by a `Rule`.
+    pub fn expansion_of_one(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// The change in value from applying the rule at `index` computed with the stored base, i.e.
+    /// `base^index - sum(values[j] * base^(index - j - 1))`.
+    ///
+    /// This is zero in exact arithmetic, so its size relative to `base^index` shows how accurately
+    /// the base was computed.
+    pub fn apply_value_delta(&self, index: isize) -> f64 {
+        let power = |exponent: isize| self.base.powi(i32::try_from(exponent).unwrap());
+        power(index)
+            - self
+                .iter()
+                .enumerate()
+                .map(|(j, value)| f64::from(value) * power(index - isize::try_from(j + 1).unwrap()))
+                .sum::<f64>()
+    }
+
+    /// Coefficients of the minimal polynomial of the base over the integers, highest degree first.
+    ///
+    /// The base is a root of `x^d - v_1 x^(d - 1) - ... - v_d` where `v_1, ..., v_d` are the rule
+    /// values. By a theorem of Brauer this polynomial is irreducible whenever the values are
+    /// non-increasing and positive, as they are for every `Rule`, so it is the minimal polynomial
+    /// and there is nothing to factor out.
+    pub fn minimal_polynomial(&self) -> Vec<i64> {
+        std::iter::once(1)
+            .chain(self.iter().map(|value| -i64::from(value)))
+            .collect()
+    }
+
+    /// For each `k` in `0..up_to`, the integer coefficients `c` with
+    /// `base^k = c[0] + c[1] * base + ... + c[d - 1] * base^(d - 1)` where `d` is the degree.
+    ///
+    /// These follow from rewriting `base^d` as `values[0] * base^(d - 1) + ... + values[d - 1]`,
+    /// so they are exact and can be used to check [`crate::Tape::value`]. Panics if a coefficient
+    /// overflows a [`Value`].
+    pub fn power_coefficients(&self, up_to: usize) -> Vec<Vec<Value>> {
+        let degree = self.degree();
+        let mut result: Vec<Vec<Value>> = Vec::with_capacity(up_to);
+        for k in 0..up_to {
+            let coefficients = if k < degree {
+                let mut unit = vec![0; degree];
+                unit[k] = 1;
+                unit
+            } else {
+                let previous = &result[k - 1];
+                let top = previous[degree - 1];
+                (0..degree)
+                    .map(|i| {
+                        let shifted = if i == 0 { 0 } else { previous[i - 1] };
+                        top.checked_mul(self.values[degree - 1 - i])
+                            .and_then(|carried| carried.checked_add(shifted))
+                            .expect("Power coefficient overflowed.")
+                    })
+                    .collect()
+            };
+            result.push(coefficients);
+        }
+        result
+    }
+
+    /// The rule whose base is `base^k`, if there is one.
+    ///
+    /// The base is the dominant eigenvalue of the companion matrix `C` of the
+    /// [`Rule::minimal_polynomial`], so `base^k` is the dominant eigenvalue of `C^k`. Its
+    /// characteristic polynomial `x^d - b_1 x^(d - 1) - ... - b_d` is found exactly from the
+    /// traces of the powers of `C^k` by Newton's identities. This gives a rule when `b_1, ..., b_d`
+    /// are non-increasing and non-negative. For integer bases that is always the case, which is
+    /// the rule [`crate::Tape::regroup`] converts to. For e.g. the golden ratio it never is for
+    /// even `k`, since then `b_d = -1`. Also returns `None` if the coefficients overflow.
+    pub fn power_rule(&self, k: usize) -> Option<Rule> {
+        type Matrix = Vec<Vec<i128>>;
+        let degree = self.degree();
+        let multiply = |a: &Matrix, b: &Matrix| -> Option<Matrix> {
+            (0..degree)
+                .map(|i| {
+                    (0..degree)
+                        .map(|j| {
+                            (0..degree).try_fold(0i128, |acc, l| {
+                                acc.checked_add(a[i][l].checked_mul(b[l][j])?)
+                            })
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+        let identity: Matrix = (0..degree)
+            .map(|i| (0..degree).map(|j| i128::from(i == j)).collect())
+            .collect();
+        let companion: Matrix = (0..degree)
+            .map(|i| {
+                (0..degree)
+                    .map(|j| match i {
+                        0 => i128::from(self.values[j]),
+                        _ => i128::from(j + 1 == i),
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut power = identity.clone();
+        for _ in 0..k {
+            power = multiply(&power, &companion)?;
+        }
+        // Power sums `traces[m - 1] = trace((C^k)^m)` of the eigenvalues of `C^k`.
+        let mut traces = Vec::with_capacity(degree);
+        let mut accumulated = identity;
+        for _ in 0..degree {
+            accumulated = multiply(&accumulated, &power)?;
+            traces.push((0..degree).try_fold(0i128, |acc, i| acc.checked_add(accumulated[i][i]))?);
+        }
+        // Newton's identities for the elementary symmetric polynomials `e_1, ..., e_d`.
+        let mut elementary = vec![1i128];
+        for j in 1..=degree {
+            let mut sum = 0i128;
+            for i in 1..=j {
+                let term = elementary[j - i].checked_mul(traces[i - 1])?;
+                sum = if i % 2 == 1 {
+                    sum.checked_add(term)?
+                } else {
+                    sum.checked_sub(term)?
+                };
+            }
+            elementary.push(sum / i128::try_from(j).unwrap());
+        }
+        // `b_j = (-1)^(j + 1) e_j`.
+        let values: Option<Vec<Value>> = elementary[1..]
+            .iter()
+            .enumerate()
+            .map(|(index, &e)| Value::try_from(if index % 2 == 0 { e } else { -e }).ok())
+            .collect();
+        Rule::from_array(values?)
+    }
+
+    /// The derivative of the [`Rule::minimal_polynomial`] at the base.
+    ///
+    /// This is the denominator of a Newton step towards the base, and the error in the computed
+    /// base is roughly the rounding error of evaluating the polynomial divided by it. A value
+    /// close to zero therefore means the base, and with it [`crate::Tape::value`], is computed
+    /// inaccurately.
+    pub fn polynomial_derivative_at_base(&self) -> f64 {
+        let degree = self.len();
+        let power = |exponent: usize| self.base.powi(i32::try_from(exponent).unwrap());
+        degree as f64 * power(degree - 1)
+            - self
+                .iter()
+                .enumerate()
+                .take(degree - 1)
+                .map(|(j, value)| {
+                    f64::from(value) * (degree - j - 1) as f64 * power(degree - j - 2)
+                })
+                .sum::<f64>()
+    }
+
+    /// The transfer matrix of the automaton recognizing standard digit sequences.
+    ///
+    /// State `k` for `k` in `0..self.len()` means that the most recently read digits are exactly
+    /// the first `k` rule values. Entry `[i][j]` is the number of digits leading from state `i` to
+    /// state `j`: from state `k`, the digits below `self[k]` lead back to state `0` and the digit
+    /// `self[k]` leads to state `k + 1`, unless that would complete a full copy of the rule.
+    ///
+    /// The dominant eigenvalue of the matrix is the base.
+    pub fn transfer_matrix(&self) -> Vec<Vec<u64>> {
+        let len = self.len();
+        let mut matrix = vec![vec![0; len]; len];
+        for (state, rule_value) in self.iter().enumerate() {
+            matrix[state][0] += u64::from(rule_value);
+            if state + 1 < len {
+                matrix[state][state + 1] += 1;
+            }
+        }
+        matrix
+    }
+
+    /// The state of the automaton described in [`Rule::transfer_matrix`] after reading `digit` in
+    /// `state`, or `None` if the digit is not allowed.
+    fn next_state(&self, state: usize, digit: Value) -> Option<usize> {
+        let rule_value = self.values[state];
+        if digit < rule_value {
+            Some(0)
+        } else if digit == rule_value && state + 1 < self.len() {
+            Some(state + 1)
+        } else {
+            None
+        }
+    }
+
+    /// The automaton recognizing standard digit sequences, read most significant digit first.
+    ///
+    /// This is the automaton described in [`Rule::transfer_matrix`] as data: state `k` means that
+    /// the most recently read digits are exactly the first `k` rule values, and the initial state
+    /// is `0`. It is deterministic since each digit leads to at most one state, and it accepts the
+    /// same sequences as [`Rule::successor_digits`] allows.
+    pub fn admissibility_automaton(&self) -> Automaton {
+        let transitions = (0..self.len())
+            .map(|state| {
+                (0..=self.first())
+                    .filter_map(|digit| Some((digit, self.next_state(state, digit)?)))
+                    .collect::<BTreeMap<_, _>>()
+            })
+            .collect();
+        Automaton::new(0, transitions)
+    }
+
+    /// Number of standard digit sequences of exactly `length` digits (including leading zeros).
+    pub fn count_standard(&self, length: usize) -> u64 {
+        let matrix = self.transfer_matrix();
+        let mut counts = vec![0; self.len()];
+        counts[0] = 1;
+        for _ in 0..length {
+            let mut next = vec![0; self.len()];
+            for (from, &count) in counts.iter().enumerate() {
+                for (to, &transitions) in matrix[from].iter().enumerate() {
+                    next[to] += count * transitions;
+                }
+            }
+            counts = next;
+        }
+        counts.into_iter().sum()
+    }
+
+    /// The topological entropy `ln(base)` of the shift of standard digit sequences.
+    pub fn topological_entropy(&self) -> f64 {
+        self.base.ln()
+    }
+
+    /// The asymptotic frequency of each digit `0..=self.first()` in the expansions of almost every
+    /// number.
+    ///
+    /// These are the digit frequencies under the Parry measure, the invariant measure of maximal
+    /// entropy. For the beta-shift it also governs the greedy expansions of Lebesgue-almost every
+    /// number. With `u` and `v` the left and right dominant eigenvectors of the transfer matrix of
+    /// the [`Rule::admissibility_automaton`], a transition from state `i` to state `j` has
+    /// frequency `u[i] * v[j] / (base * u . v)`. The frequency of a digit sums this over the
+    /// transitions reading it. The eigenvectors are found by power iteration.
+    pub fn asymptotic_digit_frequencies(&self) -> Vec<f64> {
+        let automaton = self.admissibility_automaton();
+        let states = automaton.num_states();
+        let edges: Vec<(usize, Value, usize)> = (0..states)
+            .flat_map(|from| {
+                automaton
+                    .transitions(from)
+                    .iter()
+                    .map(move |(&digit, &to)| (from, digit, to))
+            })
+            .collect();
+        let matrix = self.transfer_matrix();
+        let left = power_iteration(&matrix, true);
+        let right = power_iteration(&matrix, false);
+        let normalization = self.base * left.iter().zip(&right).map(|(u, v)| u * v).sum::<f64>();
+        let mut frequencies = vec![0.; usize::try_from(self.first()).unwrap() + 1];
+        for (from, digit, to) in edges {
+            frequencies[usize::try_from(digit).unwrap()] += left[from] * right[to] / normalization;
+        }
+        frequencies
+    }
+
+    /// The ratio `count_standard(max_len + 1) / count_standard(max_len)`, which converges to the
+    /// base as `max_len` grows, so its logarithm converges to [`Rule::topological_entropy`].
+    ///
+    /// The counts are exact, so `max_len` is limited by [`Rule::count_standard`] fitting in a
+    /// `u64`, roughly `64 / log2(base)`.
+    pub fn combinatorial_growth_rate(&self, max_len: usize) -> f64 {
+        self.count_standard(max_len + 1) as f64 / self.count_standard(max_len) as f64
+    }
+
+    /// All standard digit sequences of exactly `length` digits, most significant digit first, in
+    /// lexicographic order.
+    ///
+    /// These are the words of the language of the numeration system, i.e. the sequences with no
+    /// window of `self.len()` digits lexicographically at least the rule values.
+    pub fn words(&self, length: usize) -> impl Iterator<Item = Vec<Value>> + '_ {
+        let mut stack = vec![(vec![], 0)];
+        std::iter::from_fn(move || {
+            while let Some((word, state)) = stack.pop() {
+                if word.len() == length {
+                    return Some(word);
+                }
+                for digit in (0..=self.first()).rev() {
+                    if let Some(next_state) = self.next_state(state, digit) {
+                        let mut next_word = word.clone();
+                        next_word.push(digit);
+                        stack.push((next_word, next_state));
+                    }
+                }
+            }
+            None
+        })
+    }
+
+    /// Whether the two rules admit exactly the same standard digit sequences.
+    ///
+    /// The automata described in [`Rule::transfer_matrix`] are explored in lockstep from their
+    /// initial states, failing as soon as one accepts a digit the other rejects. Every reachable
+    /// pair of states is visited at most once, so this terminates after at most
+    /// `self.len() * other.len()` pairs. Since rules are trimmed on construction, distinct rules
+    /// are not expected to agree, but this compares the languages rather than the values.
+    pub fn same_language(&self, other: &Rule) -> bool {
+        let max_digit = self.first().max(other.first());
+        let mut visited = vec![vec![false; other.len()]; self.len()];
+        visited[0][0] = true;
+        let mut stack = vec![(0, 0)];
+        while let Some((state, other_state)) = stack.pop() {
+            for digit in 0..=max_digit {
+                match (
+                    self.next_state(state, digit),
+                    other.next_state(other_state, digit),
+                ) {
+                    (None, None) => {}
+                    (Some(next), Some(other_next)) => {
+                        if !visited[next][other_next] {
+                            visited[next][other_next] = true;
+                            stack.push((next, other_next));
+                        }
+                    }
+                    _ => return false,
+                }
+            }
+        }
+        true
+    }
+
+    /// The digits that may follow the `self.len() - 1` most recent digits `state`, oldest first,
+    /// in increasing order.
+    ///
+    /// This is the transition function of the automaton recognizing standard digit sequences
+    /// with the recent digits themselves as the state, rather than the compressed states of
+    /// [`Rule::transfer_matrix`]. A digit is only allowed if some continuation keeps the sequence
+    /// standard, so e.g. under the rule `2 1 1` the digit `2` may not follow `1 2`, even though
+    /// `1 2 2` itself is below the rule values, as `2 2` cannot be continued.
+    pub fn successor_digits(&self, state: &[Value]) -> Vec<Value> {
+        assert_eq!(state.len(), self.len() - 1);
+        let Some(state) = state
+            .iter()
+            .try_fold(0, |state, &digit| self.next_state(state, digit))
+        else {
+            return Vec::new();
+        };
+        (0..=self.first())
+            .filter(|&digit| self.next_state(state, digit).is_some())
+            .collect()
+    }
+
+    /// The sorted values of all standard tapes with digits at indices `0..n`.
+    ///
+    /// The tapes are enumerated with [`Rule::words`], so only the standard digit sequences are
+    /// visited rather than all `(first + 1)^n` sequences.
+    pub fn spectrum(&self, n: usize) -> Vec<f64> {
+        let mut values: Vec<_> = self
+            .words(n)
+            .map(|word| {
+                word.into_iter()
+                    .fold(0., |acc, digit| acc * self.base + f64::from(digit))
+            })
+            .collect();
+        values.sort_by(f64::total_cmp);
+        values.dedup();
+        values
+    }
+
+    /// The Perron eigenvector `v` of the transfer matrix `M`, satisfying `v M = base * v`,
+    /// normalized to sum to one.
+    ///
+    /// Entry `k` is the limiting fraction of standard digit sequences of length `n` that end in
+    /// state `k` of the automaton described in [`Rule::transfer_matrix`] as `n` grows.
+    /// It is computed by power iteration.
+    pub fn dominant_eigenvector(&self) -> Vec<f64> {
+        power_iteration(&self.transfer_matrix(), true)
+    }
 }
 
 impl PartialEq for Rule {
@@ -69,6 +764,25 @@ impl PartialEq for Rule {
 
 impl Eq for Rule {}
 
+/// Rules are ordered by their base.
+///
+/// Since the values of a rule are the expansion of one in its base, distinct rules have distinct
+/// bases. Ties from the limited precision of the computed bases are broken by comparing the values,
+/// so the order is total and consistent with `Eq`.
+impl PartialOrd for Rule {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rule {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.base
+            .total_cmp(&other.base)
+            .then_with(|| self.values.cmp(&other.values))
+    }
+}
+
 impl Index<usize> for Rule {
     type Output = Value;
 
@@ -77,6 +791,177 @@ impl Index<usize> for Rule {
     }
 }
 
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    /// Parses either a space separated list of rule values such as `1 1`, or the equation defining
+    /// the base such as `x^2 = x + 1`.
+    ///
+    /// In the equation form, the left hand side must be a single power of `x` and the right hand
+    /// side a sum of terms like `3`, `2x`, `2*x` or `x^2` with powers below that of the left hand
+    /// side.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = if let Some((lhs, rhs)) = s.split_once('=') {
+            parse_polynomial(lhs, rhs)?
+        } else {
+            s.split_whitespace()
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| ParseRuleError::InvalidNumber(value.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        Ok(Rule::try_from_array(values)?)
+    }
+}
+
+fn parse_term(term: &str) -> Result<(usize, Value), ParseRuleError> {
+    let parse_number = |number: &str| {
+        number
+            .parse()
+            .map_err(|_| ParseRuleError::InvalidNumber(number.to_string()))
+    };
+    let term: String = term.split_whitespace().collect();
+    if let Some((coefficient, power)) = term.split_once('x') {
+        let coefficient = coefficient.strip_suffix('*').unwrap_or(coefficient);
+        let coefficient = if coefficient.is_empty() {
+            1
+        } else {
+            parse_number(coefficient)?
+        };
+        let power = if power.is_empty() {
+            1
+        } else if let Some(power) = power.strip_prefix('^') {
+            parse_number(power)?
+        } else {
+            return Err(ParseRuleError::UnsupportedPolynomial(term));
+        };
+        Ok((usize::try_from(power).unwrap(), coefficient))
+    } else {
+        Ok((0, parse_number(&term)?))
+    }
+}
+
+/// The largest degree accepted by [`parse_polynomial`]. At higher degrees the rule polynomial
+/// overflows an `f64` at `first + 1 >= 2`, so [`Rule::try_from_array`] rejects the rule anyway,
+/// and checking first avoids allocating coefficients for an arbitrarily large exponent.
+const MAX_POLYNOMIAL_DEGREE: usize = 1023;
+
+fn parse_polynomial(lhs: &str, rhs: &str) -> Result<Vec<Value>, ParseRuleError> {
+    let unsupported = || ParseRuleError::UnsupportedPolynomial(format!("{lhs}={rhs}"));
+    if rhs.contains('=') || rhs.contains('-') || lhs.contains('+') || lhs.contains('-') {
+        return Err(unsupported());
+    }
+    let (degree, leading_coefficient) = parse_term(lhs)?;
+    if degree == 0 || degree > MAX_POLYNOMIAL_DEGREE || leading_coefficient != 1 {
+        return Err(unsupported());
+    }
+    let mut values: Vec<Option<Value>> = vec![None; degree];
+    for term in rhs.split('+') {
+        let (power, coefficient) = parse_term(term)?;
+        if power >= degree {
+            return Err(unsupported());
+        }
+        let value = &mut values[degree - 1 - power];
+        if value.is_some() {
+            return Err(ParseRuleError::DuplicatePower(power));
+        }
+        *value = Some(coefficient);
+    }
+    Ok(values.into_iter().map(|value| value.unwrap_or(0)).collect())
+}
+
+/// Standardizes the digit string `digits`, most significant digit first and the last digit at
+/// index zero, and returns the standard digit string without leading zeros.
+///
+/// Panics if the standard form has nonzero digits at negative indices, which can happen for rules
+/// with more than one distinct value, e.g. `2 2 0` in the rule `[2, 1, 1]`.
+pub fn normalize(digits: &[Value], rule: &Rule) -> Vec<Value> {
+    let (positive_values, negative_values) = crate::Tape::from_arrays(digits, [])
+        .standardize(rule)
+        .into_arrays();
+    assert!(
+        negative_values.iter().all(|&value| value == 0),
+        "The standard form of {digits:?} has a fractional part."
+    );
+    positive_values
+        .into_iter()
+        .rev()
+        .skip_while(|&value| value == 0)
+        .collect()
+}
+
+/// Computes the row vector `vector * matrix`.
+fn left_multiply(vector: &[f64], matrix: &[Vec<u64>]) -> Vec<f64> {
+    (0..matrix.len())
+        .map(|j| {
+            (0..matrix.len())
+                .map(|i| vector[i] * matrix[i][j] as f64)
+                .sum()
+        })
+        .collect()
+}
+
+/// Computes the column vector `matrix * vector`.
+fn right_multiply(matrix: &[Vec<u64>], vector: &[f64]) -> Vec<f64> {
+    matrix
+        .iter()
+        .map(|row| row.iter().zip(vector).map(|(&m, v)| m as f64 * v).sum())
+        .collect()
+}
+
+/// The dominant eigenvector of a nonnegative `matrix` with a simple dominant eigenvalue,
+/// normalized to sum to one: the left eigenvector `v M = lambda v` if `left`, and otherwise the
+/// right eigenvector `M v = lambda v`. It is found by power iteration, stopping once no entry
+/// changes by more than its relative rounding error.
+fn power_iteration(matrix: &[Vec<u64>], left: bool) -> Vec<f64> {
+    let mut vector = vec![1. / matrix.len() as f64; matrix.len()];
+    for _ in 0..10000 {
+        let next = if left {
+            left_multiply(&vector, matrix)
+        } else {
+            right_multiply(matrix, &vector)
+        };
+        let sum = next.iter().sum::<f64>();
+        let next: Vec<_> = next.into_iter().map(|x| x / sum).collect();
+        let converged = vector
+            .iter()
+            .zip(next.iter())
+            .all(|(x, y)| (x - y).abs() <= f64::EPSILON * x.abs());
+        vector = next;
+        if converged {
+            break;
+        }
+    }
+    vector
+}
+
+/// Memoizes the base of each rule per thread, so constructing the same rule repeatedly only
+/// computes the base once.
+#[cfg(feature = "cache")]
+fn cached_rule_base(rule: &[Value]) -> Option<f64> {
+    use std::{cell::RefCell, collections::HashMap};
+
+    thread_local! {
+        static BASE_CACHE: RefCell<HashMap<Vec<Value>, Option<f64>>> =
+            RefCell::new(HashMap::new());
+    }
+    BASE_CACHE.with(|cache| {
+        if let Some(&base) = cache.borrow().get(rule) {
+            return base;
+        }
+        let base = calculate_rule_base(rule);
+        cache.borrow_mut().insert(rule.to_vec(), base);
+        base
+    })
+}
+
+#[cfg(not(feature = "cache"))]
+fn cached_rule_base(rule: &[Value]) -> Option<f64> {
+    calculate_rule_base(rule)
+}
+
 fn evaluate_rule_polynomial(rule: &[Value], x: f64) -> f64 {
     let degree = i32::try_from(rule.len()).unwrap();
     -x.powi(degree)
@@ -89,22 +974,34 @@ fn evaluate_rule_polynomial(rule: &[Value], x: f64) -> f64 {
             .sum::<f64>()
 }
 
-fn calculate_rule_base(rule: &[Value]) -> f64 {
+/// The base of the rule, or `None` if [`try_base_bracket`] fails.
+fn calculate_rule_base(rule: &[Value]) -> Option<f64> {
+    try_base_bracket(rule).map(|(min, max, _, _)| (min + max) / 2.)
+}
+
+/// Bisects the rule polynomial between `rule[0]` and `rule[0] + 1` and returns the final
+/// `(min, max, min_value, max_value)`, or `None` if the polynomial stops straddling zero or
+/// evaluates to NaN because it overflows.
+///
+/// The bracket ends when `min` and `max` are adjacent floats, or collapses to a single point if
+/// the polynomial evaluates to exactly zero.
+fn try_base_bracket(rule: &[Value]) -> Option<(f64, f64, f64, f64)> {
     let mut min = f64::from(rule[0]);
     let mut max = min + 1.;
     let mut min_value = evaluate_rule_polynomial(rule, min);
     let mut max_value = evaluate_rule_polynomial(rule, max);
     loop {
-        assert!(min_value >= 0.);
-        assert!(max_value <= 0.);
-        if min == max {
-            return min;
+        if !(min_value >= 0. && max_value <= 0.) {
+            return None;
         }
         let mid = (min + max) / 2.;
-        if mid == min || mid == max {
-            return mid;
+        if min == max || mid == min || mid == max {
+            return Some((min, max, min_value, max_value));
         }
         let mid_value = evaluate_rule_polynomial(rule, mid);
+        if mid_value.is_nan() {
+            return None;
+        }
         if mid_value > 0. {
             min = mid;
             min_value = mid_value;
@@ -112,7 +1009,7 @@ fn calculate_rule_base(rule: &[Value]) -> f64 {
             max = mid;
             max_value = mid_value;
         } else {
-            return mid;
+            return Some((mid, mid, mid_value, mid_value));
         }
     }
 }
@@ -145,6 +1042,848 @@ mod test {
         assert_relative_eq!(rule.base(), phi);
     }
 
+    #[test]
+    fn cached_rule_base() {
+        for values in [vec![1, 1], vec![10], vec![3, 2, 1], vec![1, 1, 1, 1]] {
+            let expected = calculate_rule_base(&values).unwrap();
+            for _ in 0..3 {
+                assert_eq!(Rule::from_array(&values).unwrap().base(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn power_rule() {
+        let rule = Rule::integer(2).power_rule(2).unwrap();
+        assert_eq!(rule, Rule::integer(4));
+        assert_eq!(rule.base(), 4.);
+        assert_eq!(Rule::integer(3).power_rule(3), Some(Rule::integer(27)));
+        assert_eq!(Rule::integer(7).power_rule(0), Some(Rule::integer(1)));
+        assert_eq!(Rule::golden().power_rule(1), Some(Rule::golden()));
+        // phi^2 is a root of x^2 - 3x + 1.
+        assert_eq!(Rule::golden().power_rule(2), None);
+        // (1 + sqrt(3))^3 = 10 + 6 sqrt(3) is a root of x^2 - 20x - 8.
+        let rule = Rule::from_array([2, 2]).unwrap();
+        let cubed = rule.power_rule(3).unwrap();
+        assert_eq!(cubed, Rule::from_array([20, 8]).unwrap());
+        assert_relative_eq!(cubed.base(), rule.base().powi(3), max_relative = 1e-12);
+        assert_eq!(rule.power_rule(2), None);
+
+        for rule in (1..=3).flat_map(|degree| Rule::enumerate_degree(degree, 3)) {
+            for k in 1..=4 {
+                if let Some(power) = rule.power_rule(k) {
+                    assert_relative_eq!(
+                        power.base(),
+                        rule.base().powi(i32::try_from(k).unwrap()),
+                        max_relative = 1e-12
+                    );
+                }
+            }
+        }
+        assert_eq!(Rule::integer(Value::MAX).power_rule(5), None);
+    }
+
+    #[test]
+    fn asymptotic_digit_frequencies() {
+        let frequencies = Rule::integer(10).asymptotic_digit_frequencies();
+        assert_eq!(frequencies.len(), 11);
+        for &frequency in &frequencies[..10] {
+            assert_relative_eq!(frequency, 0.1, max_relative = 1e-12);
+        }
+        assert_eq!(frequencies[10], 0.);
+
+        // Under the Parry measure of the golden mean shift, ones have frequency 1 / (phi^2 + 1).
+        let phi = Rule::golden().base();
+        let frequencies = Rule::golden().asymptotic_digit_frequencies();
+        assert_relative_eq!(frequencies[1], 1. / (phi * phi + 1.), max_relative = 1e-12);
+
+        for rule in [
+            Rule::golden(),
+            Rule::tribonacci(),
+            Rule::from_array([3, 2, 1]).unwrap(),
+            Rule::from_array([2, 2, 1, 1]).unwrap(),
+        ] {
+            let frequencies = rule.asymptotic_digit_frequencies();
+            assert_eq!(frequencies.len(), rule.first() as usize + 1);
+            assert_relative_eq!(frequencies.iter().sum::<f64>(), 1., max_relative = 1e-12);
+            assert!(frequencies.iter().all(|&frequency| frequency > 0.));
+        }
+    }
+
+    #[test]
+    fn check_digits() {
+        let rule = Rule::from_array([2, 1]).unwrap();
+        assert_eq!(rule.check_digits(&[2, 0, 1, 2, 1]), Ok(()));
+        assert_eq!(rule.check_digits(&[]), Ok(()));
+        assert_eq!(rule.check_digits(&[2, 0, 3, 1, 4]), Err(2));
+        assert_eq!(Rule::golden().check_digits(&[2]), Err(0));
+    }
+
+    #[test]
+    fn from_iter() {
+        let rule = Rule::from_iter((1..=1).map(|x| x + 1).chain(std::iter::repeat_n(1, 3)));
+        assert_eq!(rule.unwrap(), Rule::from_array([2, 1, 1, 1]).unwrap());
+        assert_eq!(
+            Rule::from_iter((0..4).rev().map(|x| x * 2)).unwrap(),
+            Rule::from_array([6, 4, 2]).unwrap()
+        );
+        assert_eq!(
+            Rule::from_iter(std::iter::once(7)).unwrap(),
+            Rule::integer(7)
+        );
+        assert!(Rule::from_iter(std::iter::empty()).is_none());
+        assert!(Rule::from_iter([0, 0]).is_none());
+        assert!(Rule::from_iter(1..=3).is_none());
+        assert!(Rule::from_iter([2, 0, 1]).is_none());
+    }
+
+    #[test]
+    fn bits_per_digit() {
+        assert_eq!(Rule::golden().bits_per_digit(), 1);
+        assert_eq!(Rule::integer(2).bits_per_digit(), 2);
+        assert_eq!(Rule::from_array([3, 2, 1]).unwrap().bits_per_digit(), 2);
+        assert_eq!(Rule::integer(10).bits_per_digit(), 4);
+        assert_eq!(Rule::integer(16).bits_per_digit(), 5);
+        assert_eq!(Rule::integer(Value::MAX).bits_per_digit(), 32);
+    }
+
+    #[test]
+    fn value_sum() {
+        assert_eq!(Rule::tribonacci().value_sum(), 3);
+        assert_eq!(Rule::integer(10).value_sum(), 10);
+        assert_eq!(Rule::from_array([3, 2, 1]).unwrap().value_sum(), 6);
+        for rule in (1..=4).flat_map(|degree| Rule::enumerate_degree(degree, 4)) {
+            assert!(f64::from(rule.first()) <= rule.base());
+            assert!(rule.base() <= rule.value_sum() as f64);
+        }
+    }
+
+    #[test]
+    fn multinacci() {
+        assert_eq!(Rule::golden().multinacci_order(), Some(2));
+        assert_eq!(Rule::tribonacci().multinacci_order(), Some(3));
+        assert_eq!(Rule::multinacci(2), Rule::golden());
+        assert_eq!(Rule::multinacci(5).multinacci_order(), Some(5));
+        assert_eq!(Rule::integer(1).multinacci_order(), Some(1));
+        assert_eq!(Rule::integer(2).multinacci_order(), None);
+        assert_eq!(Rule::from_array([2, 1]).unwrap().multinacci_order(), None);
+    }
+
+    #[test]
+    fn named_rules() {
+        let phi = (1. + 5_f64.sqrt()) / 2.;
+        assert_eq!(Rule::golden(), Rule::from_array([1, 1]).unwrap());
+        assert_relative_eq!(Rule::golden().base(), phi);
+        assert_eq!(Rule::integer(10), Rule::from_array([10]).unwrap());
+        assert_relative_eq!(Rule::integer(10).base(), 10.);
+        assert_eq!(Rule::tribonacci(), Rule::from_array([1, 1, 1]).unwrap());
+        assert_relative_eq!(Rule::tribonacci().base(), 1.839286755214161);
+    }
+
+    #[test]
+    #[should_panic]
+    fn integer_zero() {
+        Rule::integer(0);
+    }
+
+    #[test]
+    fn max_reliable_index() {
+        assert_eq!(Rule::integer(2).max_reliable_index(), 52);
+        assert_eq!(Rule::integer(10).max_reliable_index(), 15);
+        assert_eq!(Rule::integer(16).max_reliable_index(), 13);
+        assert_eq!(Rule::golden().max_reliable_index(), 76);
+        assert_eq!(Rule::integer(1).max_reliable_index(), isize::MAX);
+        let indices: Vec<_> = (2..20)
+            .map(|n| Rule::integer(n).max_reliable_index())
+            .collect();
+        assert!(indices.iter().tuple_windows().all(|(a, b)| a >= b));
+    }
+
+    #[test]
+    fn ord() {
+        let mut rules = [Rule::golden(), Rule::integer(2), Rule::integer(1)];
+        rules.sort();
+        assert_eq!(rules, [Rule::integer(1), Rule::golden(), Rule::integer(2)]);
+        assert!(Rule::tribonacci() > Rule::golden());
+        assert!(Rule::from_array([2, 1]).unwrap() > Rule::integer(2));
+        assert_eq!(
+            Rule::from_array([1, 1, 0]).unwrap().cmp(&Rule::golden()),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn base_string() {
+        let rule = Rule::from_array([1, 1]).unwrap();
+        assert!(rule.base_string(12).starts_with("1.6180339887"));
+        assert_eq!(rule.base_string(3), "1.618");
+        let rule = Rule::from_array([10]).unwrap();
+        assert_eq!(rule.base_string(2), "10.00");
+    }
+
+    #[test]
+    fn base_continued_fraction() {
+        assert_eq!(Rule::golden().base_continued_fraction(10), vec![1; 10]);
+        assert_eq!(Rule::integer(10).base_continued_fraction(10), vec![10]);
+        // 1 + sqrt(2) = [2; 2, 2, ...]
+        assert_eq!(
+            Rule::from_array([2, 1])
+                .unwrap()
+                .base_continued_fraction(10),
+            vec![2; 10]
+        );
+        assert!(Rule::golden().base_continued_fraction(0).is_empty());
+    }
+
+    #[test]
+    fn base_rational_approx() {
+        let rule = Rule::from_array([1, 1]).unwrap();
+        assert_eq!(rule.base_rational_approx(1), (2, 1));
+        assert_eq!(rule.base_rational_approx(100), (144, 89));
+        assert_eq!(rule.base_rational_approx(144), (233, 144));
+        let rule = Rule::from_array([10]).unwrap();
+        assert_eq!(rule.base_rational_approx(1000), (10, 1));
+        let rule = Rule::from_array([2, 1]).unwrap();
+        let (p, q) = rule.base_rational_approx(1000);
+        assert!(q <= 1000);
+        assert!((p as f64 / q as f64 - rule.base()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn base_rational_approx_overflow() {
+        for rule in [
+            Rule::golden(),
+            Rule::from_array([1 << 20, 1 << 20]).unwrap(),
+        ] {
+            let (p, q) = rule.base_rational_approx(u64::MAX);
+            assert_relative_eq!(p as f64 / q as f64, rule.base(), max_relative = 1e-12);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The denominator bound must be positive.")]
+    fn base_rational_approx_zero() {
+        Rule::golden().base_rational_approx(0);
+    }
+
+    #[test]
+    fn debug_base_bracket() {
+        for rule in [
+            Rule::golden(),
+            Rule::tribonacci(),
+            Rule::integer(10),
+            Rule::from_array([3, 2, 1]).unwrap(),
+            Rule::from_array([9, 9, 9, 9, 9]).unwrap(),
+            Rule::multinacci(8),
+        ] {
+            let (min, max, min_value, max_value) = rule.debug_base_bracket();
+            assert!(min <= rule.base() && rule.base() <= max);
+            assert!(max - min <= f64::EPSILON * max);
+            assert!(min_value >= 0.);
+            assert!(max_value <= 0.);
+        }
+        let (min, _, min_value, _) = Rule::integer(2).debug_base_bracket();
+        assert_eq!((min, min_value), (2., 0.));
+        // Increasing values can have their base outside the initial bracket.
+        assert_eq!(try_base_bracket(&[1, 5]), None);
+    }
+
+    #[test]
+    fn base_error_bound() {
+        let bound = Rule::golden().base_error_bound();
+        assert!(bound < 1e-14);
+        assert_relative_eq!(
+            Rule::golden().base(),
+            (1. + 5f64.sqrt()) / 2.,
+            max_relative = bound
+        );
+        for rule in [
+            Rule::golden(),
+            Rule::tribonacci(),
+            Rule::from_array([3, 2, 1]).unwrap(),
+            Rule::from_array([9, 9, 9, 9, 9]).unwrap(),
+        ] {
+            let bound = rule.base_error_bound();
+            let base = rule.base();
+            assert!(evaluate_rule_polynomial(rule.values(), base * (1. - bound)) >= 0.);
+            assert!(evaluate_rule_polynomial(rule.values(), base * (1. + bound)) <= 0.);
+        }
+    }
+
+    #[test]
+    fn integer_part_length() {
+        let rule = Rule::integer(2);
+        assert_eq!(rule.integer_part_length(100.), 7);
+        assert_eq!(rule.integer_part_length(128.), 8);
+        assert_eq!(rule.integer_part_length(127.), 7);
+        assert_eq!(rule.integer_part_length(1.), 1);
+        assert_eq!(rule.integer_part_length(0.5), 0);
+        assert_eq!(rule.integer_part_length(0.), 0);
+        assert_eq!(rule.integer_part_length(-3.), 0);
+        assert_eq!(rule.integer_part_length(f64::NEG_INFINITY), 0);
+        assert_eq!(rule.integer_part_length(f64::INFINITY), usize::MAX);
+        assert_eq!(rule.integer_part_length(f64::MAX), 1024);
+        assert_eq!(Rule::integer(1).integer_part_length(0.5), 0);
+        assert_eq!(Rule::integer(1).integer_part_length(-1.), 0);
+        assert_eq!(Rule::integer(1).integer_part_length(1.), 1);
+        assert_eq!(Rule::integer(1).integer_part_length(7.5), 7);
+        assert_eq!(Rule::integer(1).integer_part_length(1e300), usize::MAX);
+        assert_eq!(
+            Rule::integer(1).integer_part_length(f64::INFINITY),
+            usize::MAX
+        );
+        let rule = Rule::integer(10);
+        for exponent in 0..15 {
+            assert_eq!(
+                rule.integer_part_length(10f64.powi(exponent)),
+                exponent as usize + 1
+            );
+        }
+        let rule = Rule::golden();
+        assert_eq!(rule.integer_part_length(rule.base()), 2);
+        let tape = crate::Tape::from_f64(1000., &rule, 0);
+        assert_eq!(
+            rule.integer_part_length(1000.),
+            usize::try_from(tape.range().1).unwrap()
+        );
+    }
+
+    #[test]
+    fn integer_base() {
+        assert_eq!(Rule::integer(10).integer_base(), Some(10));
+        assert_eq!(Rule::integer(1).integer_base(), Some(1));
+        assert_eq!(Rule::from_array([4, 0]).unwrap().integer_base(), Some(4));
+        assert_eq!(Rule::golden().integer_base(), None);
+        assert_eq!(Rule::tribonacci().integer_base(), None);
+        // The base of this rule is within 1e-4 of 10 but irrational.
+        let rule = Rule::from_array([9, 9, 9, 9, 9]).unwrap();
+        assert!((rule.base() - 10.).abs() < 1e-4);
+        assert_eq!(rule.integer_base(), None);
+    }
+
+    #[test]
+    fn base_rational() {
+        for n in 1..20 {
+            assert_eq!(Rule::integer(n).base_rational(), Some((u64::from(n), 1)));
+        }
+        assert_eq!(
+            Rule::from_array([3, 0, 0]).unwrap().base_rational(),
+            Some((3, 1))
+        );
+        assert_eq!(Rule::golden().base_rational(), None);
+        assert_eq!(Rule::from_array([2, 2]).unwrap().base_rational(), None);
+        assert_eq!(Rule::tribonacci().base_rational(), None);
+    }
+
+    fn dominant_eigenvalue(matrix: &[Vec<u64>]) -> f64 {
+        let mut vector = vec![1.; matrix.len()];
+        let mut eigenvalue = 0.;
+        for _ in 0..1000 {
+            let next = left_multiply(&vector, matrix);
+            eigenvalue = next.iter().sum::<f64>() / vector.iter().sum::<f64>();
+            vector = next.iter().map(|x| x / eigenvalue).collect();
+        }
+        eigenvalue
+    }
+
+    #[test]
+    fn dominant_eigenvector() {
+        for rule in [
+            Rule::golden(),
+            Rule::tribonacci(),
+            Rule::integer(10),
+            Rule::from_array([3, 2, 1]).unwrap(),
+            Rule::from_array([5, 1, 1, 1]).unwrap(),
+        ] {
+            let vector = rule.dominant_eigenvector();
+            assert_eq!(vector.len(), rule.len());
+            assert!(vector.iter().all(|&x| x > 0.));
+            assert_relative_eq!(vector.iter().sum::<f64>(), 1.);
+            let image = left_multiply(&vector, &rule.transfer_matrix());
+            for (x, y) in vector.iter().zip(image) {
+                assert_relative_eq!(x * rule.base(), y, max_relative = 1e-12);
+            }
+            let right = power_iteration(&rule.transfer_matrix(), false);
+            assert_relative_eq!(right.iter().sum::<f64>(), 1.);
+            let image = right_multiply(&rule.transfer_matrix(), &right);
+            for (x, y) in right.iter().zip(image) {
+                assert_relative_eq!(x * rule.base(), y, max_relative = 1e-12);
+            }
+        }
+        let phi = Rule::golden().base();
+        let vector = Rule::golden().dominant_eigenvector();
+        assert_relative_eq!(vector[0], phi / (phi + 1.), max_relative = 1e-12);
+    }
+
+    #[test]
+    fn apply_value_delta() {
+        for rule in [
+            Rule::golden(),
+            Rule::tribonacci(),
+            Rule::integer(10),
+            Rule::from_array([3, 2, 1]).unwrap(),
+            Rule::from_array([7, 7, 7, 2]).unwrap(),
+        ] {
+            for index in [-10, -1, 0, 1, 5, 20] {
+                let scale = rule.base().powi(index);
+                let delta = rule.apply_value_delta(isize::try_from(index).unwrap());
+                assert!(delta.abs() <= scale * 1e-14, "{rule:?} {index} {delta}");
+            }
+        }
+    }
+
+    #[test]
+    fn minimal_polynomial() {
+        assert_eq!(Rule::golden().minimal_polynomial(), vec![1, -1, -1]);
+        assert_eq!(Rule::integer(10).minimal_polynomial(), vec![1, -10]);
+        assert_eq!(
+            Rule::from_array([3, 2, 1, 0]).unwrap().minimal_polynomial(),
+            vec![1, -3, -2, -1]
+        );
+        // By the rational root theorem, a rational root would be an integer dividing the constant
+        // term.
+        for rule in Rule::enumerate_degree(3, 5) {
+            let polynomial = rule.minimal_polynomial();
+            let constant = polynomial.last().unwrap().abs();
+            for root in (1..=constant).flat_map(|root| [root, -root]) {
+                let value = polynomial.iter().fold(0, |acc, &c| acc * root + c);
+                assert_ne!(value, 0, "{rule:?} has root {root}");
+            }
+        }
+    }
+
+    #[test]
+    fn power_coefficients() {
+        // phi^k = F(k - 1) + F(k) * phi.
+        let coefficients = Rule::golden().power_coefficients(20);
+        assert_eq!(coefficients.len(), 20);
+        assert_eq!(coefficients[0], vec![1, 0]);
+        assert_eq!(coefficients[1], vec![0, 1]);
+        assert_eq!(coefficients[2], vec![1, 1]);
+        for k in 2..20 {
+            assert_eq!(
+                coefficients[k][1],
+                coefficients[k - 1][1] + coefficients[k - 2][1]
+            );
+            assert_eq!(coefficients[k][0], coefficients[k - 1][1]);
+        }
+        assert_eq!(coefficients[10], vec![34, 55]);
+
+        assert_eq!(
+            Rule::integer(3).power_coefficients(4),
+            vec![vec![1], vec![3], vec![9], vec![27]]
+        );
+        for rule in [Rule::tribonacci(), Rule::from_array([3, 2, 1]).unwrap()] {
+            let base = rule.base();
+            for (k, coefficients) in rule.power_coefficients(12).into_iter().enumerate() {
+                let value: f64 = coefficients
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &c)| f64::from(c) * base.powi(i as i32))
+                    .sum();
+                assert_relative_eq!(value, base.powi(k as i32), max_relative = 1e-12);
+            }
+        }
+        assert!(Rule::golden().power_coefficients(0).is_empty());
+    }
+
+    #[test]
+    fn polynomial_derivative_at_base() {
+        assert_relative_eq!(Rule::golden().polynomial_derivative_at_base(), 5f64.sqrt());
+        assert_relative_eq!(Rule::integer(10).polynomial_derivative_at_base(), 1.);
+        let rule = Rule::tribonacci();
+        let base = rule.base();
+        assert_relative_eq!(
+            rule.polynomial_derivative_at_base(),
+            3. * base * base - 2. * base - 1.
+        );
+        for rule in Rule::enumerate_degree(4, 3) {
+            assert!(rule.polynomial_derivative_at_base() > 0.);
+        }
+    }
+
+    #[test]
+    fn transfer_matrix() {
+        let rule = Rule::from_array([1, 1]).unwrap();
+        assert_eq!(rule.transfer_matrix(), vec![vec![1, 1], vec![1, 0]]);
+        let rule = Rule::from_array([3, 2, 1]).unwrap();
+        assert_eq!(
+            rule.transfer_matrix(),
+            vec![vec![3, 1, 0], vec![2, 0, 1], vec![1, 0, 0]]
+        );
+        for values in [
+            vec![1, 1],
+            vec![10],
+            vec![2, 1],
+            vec![3, 2, 1],
+            vec![1, 1, 1, 1],
+        ] {
+            let rule = Rule::from_array(values).unwrap();
+            assert_relative_eq!(
+                dominant_eigenvalue(&rule.transfer_matrix()),
+                rule.base(),
+                max_relative = 1e-9
+            );
+        }
+    }
+
+    #[test]
+    fn count_standard() {
+        let fibonacci = [1, 2, 3, 5, 8, 13, 21, 34];
+        for (length, &count) in fibonacci.iter().enumerate() {
+            assert_eq!(Rule::golden().count_standard(length), count);
+        }
+        assert_eq!(Rule::integer(10).count_standard(3), 1000);
+    }
+
+    #[test]
+    fn entropy() {
+        assert_relative_eq!(Rule::integer(2).topological_entropy(), 2f64.ln());
+        assert_relative_eq!(Rule::integer(10).combinatorial_growth_rate(5), 10.);
+        for rule in [
+            Rule::golden(),
+            Rule::tribonacci(),
+            Rule::from_array([3, 2, 1]).unwrap(),
+            Rule::from_array([2, 1, 1]).unwrap(),
+        ] {
+            let coarse = rule.combinatorial_growth_rate(5);
+            let fine = rule.combinatorial_growth_rate(30);
+            assert!((fine - rule.base()).abs() <= (coarse - rule.base()).abs());
+            assert_relative_eq!(fine, rule.base(), max_relative = 1e-6);
+            assert_relative_eq!(fine.ln(), rule.topological_entropy(), max_relative = 1e-6);
+        }
+    }
+
+    #[test]
+    fn words() {
+        let words: Vec<_> = Rule::golden().words(3).collect();
+        assert_eq!(
+            words,
+            vec![
+                vec![0, 0, 0],
+                vec![0, 0, 1],
+                vec![0, 1, 0],
+                vec![1, 0, 0],
+                vec![1, 0, 1]
+            ]
+        );
+        for length in 0..10 {
+            assert!(Rule::golden()
+                .words(length)
+                .all(|word| !word.windows(2).any(|window| window == [1, 1])));
+        }
+        for rule in [
+            Rule::golden(),
+            Rule::tribonacci(),
+            Rule::from_array([3, 2, 1]).unwrap(),
+        ] {
+            for length in 0..6 {
+                assert_eq!(
+                    rule.words(length).count() as u64,
+                    rule.count_standard(length)
+                );
+                assert!(rule
+                    .words(length)
+                    .all(|word| crate::Tape::from_arrays(word, []).is_standard(&rule)));
+            }
+        }
+    }
+
+    #[test]
+    fn admissibility_automaton() {
+        let automaton = Rule::golden().admissibility_automaton();
+        assert_eq!(automaton.num_states(), 2);
+        assert_eq!(automaton.initial(), 0);
+        assert_eq!(automaton.transitions(0), &BTreeMap::from([(0, 0), (1, 1)]));
+        assert_eq!(automaton.transitions(1), &BTreeMap::from([(0, 0)]));
+        assert!(automaton.accepts([1, 0, 1, 0, 0, 1]));
+        assert!(!automaton.accepts([1, 0, 1, 1]));
+        assert!(!automaton.accepts([2]));
+
+        for rule in [
+            Rule::tribonacci(),
+            Rule::from_array([2, 1, 1]).unwrap(),
+            Rule::from_array([3, 2]).unwrap(),
+        ] {
+            let automaton = rule.admissibility_automaton();
+            for length in 0..6 {
+                let accepted = std::iter::repeat_n(0..=rule.first(), length)
+                    .multi_cartesian_product()
+                    .filter(|word| automaton.accepts(word.iter().copied()))
+                    .collect_vec();
+                assert_eq!(accepted, rule.words(length).collect_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn successor_digits() {
+        let rule = Rule::golden();
+        assert_eq!(rule.successor_digits(&[1]), vec![0]);
+        assert_eq!(rule.successor_digits(&[0]), vec![0, 1]);
+        assert_eq!(
+            Rule::integer(10).successor_digits(&[]),
+            (0..10).collect_vec()
+        );
+        let rule = Rule::from_array([2, 1, 1]).unwrap();
+        assert_eq!(rule.successor_digits(&[2, 1]), vec![0]);
+        assert_eq!(rule.successor_digits(&[2, 0]), vec![0, 1, 2]);
+        assert_eq!(rule.successor_digits(&[1, 2]), vec![0, 1]);
+        assert_eq!(rule.successor_digits(&[2, 2]), vec![]);
+
+        // Extending every standard word by its successor digits gives the longer words.
+        for rule in [
+            Rule::golden(),
+            Rule::tribonacci(),
+            Rule::from_array([3, 2, 1]).unwrap(),
+        ] {
+            let context_len = rule.len() - 1;
+            for length in 0..8 {
+                let extended = rule
+                    .words(length)
+                    .flat_map(|word| {
+                        let mut state = vec![0; context_len.saturating_sub(word.len())];
+                        state.extend(&word[word.len().saturating_sub(context_len)..]);
+                        rule.successor_digits(&state).into_iter().map(move |digit| {
+                            let mut next = word.clone();
+                            next.push(digit);
+                            next
+                        })
+                    })
+                    .collect_vec();
+                assert_eq!(extended, rule.words(length + 1).collect_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn same_language() {
+        let golden = Rule::golden();
+        assert!(golden.same_language(&Rule::from_array([1, 1, 0]).unwrap()));
+        assert!(golden.same_language(&golden));
+        assert!(!golden.same_language(&Rule::integer(2)));
+        assert!(!golden.same_language(&Rule::tribonacci()));
+        assert!(!Rule::from_array([2, 1])
+            .unwrap()
+            .same_language(&Rule::from_array([2, 1, 1]).unwrap()));
+        let rules: Vec<_> = (1..=3)
+            .flat_map(|degree| Rule::enumerate_degree(degree, 2))
+            .collect();
+        for (a, b) in rules.iter().tuple_combinations() {
+            assert_eq!(a.same_language(b), a == b);
+        }
+    }
+
+    #[test]
+    fn spectrum() {
+        let rule = Rule::golden();
+        for n in 0..10 {
+            let spectrum = rule.spectrum(n);
+            assert_eq!(spectrum.len() as u64, rule.count_standard(n));
+            assert!(spectrum.iter().tuple_windows().all(|(a, b)| a < b));
+        }
+        let phi = rule.base();
+        let spectrum = rule.spectrum(3);
+        let expected = [0., 1., phi, phi * phi, phi * phi + 1.];
+        assert_eq!(spectrum.len(), expected.len());
+        for (value, expected) in spectrum.into_iter().zip(expected) {
+            assert_relative_eq!(value, expected);
+        }
+        assert_eq!(Rule::integer(10).spectrum(2).len(), 100);
+    }
+
+    #[test]
+    fn rule_from_array_boundaries() {
+        assert_eq!(Rule::try_from_array([]).unwrap_err(), RuleError::Empty);
+        assert_eq!(
+            Rule::try_from_array([0]).unwrap_err(),
+            RuleError::LeadingZero
+        );
+        assert_eq!(
+            Rule::try_from_array([0, 0]).unwrap_err(),
+            RuleError::LeadingZero
+        );
+        assert_eq!(
+            Rule::try_from_array([0, 1]).unwrap_err(),
+            RuleError::LeadingZero
+        );
+        assert_eq!(
+            Rule::try_from_array([2, 0, 1]).unwrap_err(),
+            RuleError::Increasing {
+                index: 2,
+                previous: 0,
+                next: 1
+            }
+        );
+        assert_eq!(Rule::from_array([2, 1, 0, 0]).unwrap().values(), &[2, 1]);
+        assert_eq!(
+            Rule::from_array([1, 1, 0]).unwrap(),
+            Rule::from_array([1, 1]).unwrap()
+        );
+        assert_eq!(Rule::from_array([3, 0]).unwrap().values(), &[3]);
+    }
+
+    #[test]
+    fn rule_from_array_imprecise_base() {
+        assert_eq!(
+            Rule::try_from_array([Value::MAX, Value::MAX]).unwrap_err(),
+            RuleError::ImpreciseBase {
+                first: Value::MAX,
+                degree: 2
+            }
+        );
+        // Used to panic while bracketing the base, as 3^38 has more bits than an f64 mantissa.
+        assert_eq!(
+            Rule::try_from_array([2; 38]).unwrap_err(),
+            RuleError::ImpreciseBase {
+                first: 2,
+                degree: 38
+            }
+        );
+        assert_eq!(Rule::integer(Value::MAX).base(), f64::from(Value::MAX));
+        // The largest first value allowed for rules with more than one value.
+        let rule = Rule::from_array([(1 << 26) - 1, 1]).unwrap();
+        assert!(
+            rule.base() > f64::from(rule.first()) && rule.base() < f64::from(rule.first()) + 1.
+        );
+        assert!(Rule::from_array([1 << 26, 1]).is_none());
+        assert!(Rule::from_array([1 << 30, 1]).is_none());
+        // Long rules are only rejected once the bracket actually fails.
+        let rule = Rule::from_array([165_140, 5, 5]).unwrap();
+        assert!(
+            rule.base() > f64::from(rule.first()) && rule.base() < f64::from(rule.first()) + 1.
+        );
+        assert_eq!(Rule::from_array([1; 53]).unwrap().base(), 2.);
+        assert_eq!(Rule::from_array([1; 100]).unwrap().base(), 2.);
+        assert_eq!(Rule::from_array([2; 60]).unwrap().base(), 3.);
+        assert_eq!(Rule::multinacci(53).multinacci_order(), Some(53));
+        assert_eq!(Rule::multinacci(1023).multinacci_order(), Some(1023));
+        assert_eq!(
+            Rule::try_from_array([1; 1024]).unwrap_err(),
+            RuleError::ImpreciseBase {
+                first: 1,
+                degree: 1024
+            }
+        );
+    }
+
+    #[test]
+    fn from_str() {
+        let golden = Rule::from_array([1, 1]).unwrap();
+        assert_eq!("1 1".parse::<Rule>().unwrap(), golden);
+        assert_eq!("x^2 = x + 1".parse::<Rule>().unwrap(), golden);
+        assert_eq!("x^2=1+x".parse::<Rule>().unwrap(), golden);
+        assert_eq!("x^3 = x^2 + x".parse::<Rule>().unwrap(), golden);
+        assert_eq!(
+            "x^3 = 3x^2 + 2*x + 1".parse::<Rule>().unwrap(),
+            "3 2 1".parse::<Rule>().unwrap()
+        );
+        assert_eq!(
+            "x = 10".parse::<Rule>().unwrap(),
+            Rule::from_array([10]).unwrap()
+        );
+
+        assert_eq!(
+            "1 a".parse::<Rule>().unwrap_err(),
+            ParseRuleError::InvalidNumber("a".to_string())
+        );
+        assert_eq!(
+            "".parse::<Rule>().unwrap_err(),
+            ParseRuleError::Rule(RuleError::Empty)
+        );
+        assert_eq!(
+            "x^2 = 1".parse::<Rule>().unwrap_err(),
+            ParseRuleError::Rule(RuleError::LeadingZero)
+        );
+        assert!(matches!(
+            "x^3 = x^2 + 2".parse::<Rule>().unwrap_err(),
+            ParseRuleError::Rule(RuleError::Increasing { .. })
+        ));
+        assert_eq!(
+            "x^2 = x + x".parse::<Rule>().unwrap_err(),
+            ParseRuleError::DuplicatePower(1)
+        );
+        for unsupported in [
+            "x^2 = x - 1",
+            "2x^2 = x + 1",
+            "x^2 = x^2 + 1",
+            "x^2 = y + 1",
+        ] {
+            assert!(unsupported.parse::<Rule>().is_err(), "{unsupported}");
+        }
+        assert_eq!(
+            "x^1023 = x^1022".parse::<Rule>().unwrap(),
+            Rule::from_array([1]).unwrap()
+        );
+        assert_eq!(
+            "x^4294967295 = 1".parse::<Rule>().unwrap_err(),
+            ParseRuleError::UnsupportedPolynomial("x^4294967295 = 1".to_string())
+        );
+    }
+
+    #[test]
+    fn enumerate_degree() {
+        let rules: Vec<_> = Rule::enumerate_degree(2, 2).collect();
+        assert_eq!(rules.len(), 3);
+        for values in [[1, 1], [2, 1], [2, 2]] {
+            assert!(rules.contains(&Rule::from_array(values).unwrap()));
+        }
+        assert_eq!(Rule::enumerate_degree(1, 10).count(), 10);
+        assert_eq!(Rule::enumerate_degree(3, 3).count(), 10);
+        assert!(Rule::enumerate_degree(3, 3).all(|rule| rule.len() == 3));
+        assert_eq!(Rule::enumerate_degree(0, 3).count(), 0);
+        assert_eq!(Rule::enumerate_degree(2, 0).count(), 0);
+    }
+
+    #[test]
+    fn with_first_digit() {
+        let rule = Rule::from_array([2, 1]).unwrap();
+        assert_eq!(
+            rule.with_first_digit(5),
+            Some(Rule::from_array([5, 1]).unwrap())
+        );
+        assert_eq!(
+            rule.with_first_digit(1),
+            Some(Rule::from_array([1, 1]).unwrap())
+        );
+        assert_eq!(rule.with_first_digit(0), None);
+        let rule = Rule::from_array([3, 2]).unwrap();
+        assert_eq!(rule.with_first_digit(1), None);
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(super::normalize(&[1, 1], &Rule::golden()), vec![1, 0, 0]);
+        assert_eq!(
+            super::normalize(&[0, 1, 0, 1, 1], &Rule::golden()),
+            vec![1, 0, 0, 0, 0]
+        );
+        assert_eq!(super::normalize(&[9, 9], &Rule::integer(10)), vec![9, 9]);
+        assert_eq!(super::normalize(&[0, 0], &Rule::integer(10)), vec![]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn normalize_fractional() {
+        super::normalize(&[2, 2, 0], &Rule::from_array([2, 1, 1]).unwrap());
+    }
+
+    #[test]
+    fn expansion_of_one() {
+        for n in 1..=10 {
+            let rule = Rule::from_array([n]).unwrap();
+            assert_eq!(rule.expansion_of_one(), &[n]);
+        }
+        // Frougny and Solomyak's criterion holds for every rule by construction.
+        for rule in (1..=4).flat_map(|degree| Rule::enumerate_degree(degree, 4)) {
+            let expansion = rule.expansion_of_one();
+            assert!(expansion.last().is_some_and(|&last| last > 0));
+            assert!(expansion.iter().tuple_windows().all(|(a, b)| a >= b));
+        }
+        // An expansion of one that increases somewhere is not a rule.
+        assert!(Rule::from_array([1, 0, 1]).is_none());
+    }
+
     proptest! {
         #![proptest_config(ProptestConfig {
             timeout: 10,
@@ -1,22 +1,71 @@
 use std::{
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Display,
+    hash::{Hash, Hasher},
     ops::{Add, AddAssign, Index, IndexMut},
 };
 
+use itertools::{EitherOrBoth, Itertools};
 use thiserror::Error;
 
-use crate::{rule::Rule, Value};
+use crate::{rule::Rule, SignedValue, Value};
 
+/// Why a rule application failed.
+///
+/// This used to be a struct with the fields of [`ApplyRuleError::InsufficientDigit`]; match on
+/// that variant where those fields were read directly.
 #[derive(Clone, Debug, Error)]
-#[error("Rule application at index {application_index} failed at rule index {rule_index} because rule value {rule_value} is greater than tape value {tape_value}.")]
-pub struct ApplyRuleError {
-    application_index: isize,
-    rule_index: usize,
-    rule_value: Value,
-    tape_value: Value,
+pub enum ApplyRuleError {
+    #[error("Rule application at index {application_index} failed at rule index {rule_index} because rule value {rule_value} is greater than tape value {tape_value}.")]
+    InsufficientDigit {
+        application_index: isize,
+        rule_index: usize,
+        rule_value: Value,
+        tape_value: Value,
+    },
+    #[error(transparent)]
+    IndexOverflow(#[from] IndexOverflow),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("Index {index} minus {offset} is below isize::MIN.")]
+pub struct IndexOverflow {
+    index: isize,
+    offset: usize,
+}
+
+#[derive(Clone, Debug, Error)]
+#[error("Expected a rule of degree {expected}, but the rule has degree {actual}.")]
+pub struct DegreeError {
+    expected: usize,
+    actual: usize,
+}
+
+/// The number of tapes [`Tape::optimal_standardize`] may visit before giving up.
+pub const OPTIMAL_STANDARDIZE_MAX_STATES: usize = 1 << 20;
+
+/// Relative tolerance used when choosing digits in greedy expansions.
+pub(crate) const GREEDY_TOLERANCE: f64 = 1e-12;
+
+/// Computes the value of the `(index, value)` digits without materializing a [`Tape`], so digits
+/// can be streamed from e.g. a file.
+///
+/// The digits may come in any order and indices may repeat, in which case the digits are summed.
+/// Summing in descending order of index, as [`Tape::value`] does, gives the same result.
+pub fn value_from_digits(digits: impl Iterator<Item = (isize, Value)>, rule: &Rule) -> f64 {
+    digits
+        .map(|(index, value)| f64::from(value) * rule.base().powi(i32::try_from(index).unwrap()))
+        .sum::<f64>()
+}
+
+/// Memoized standard forms for [`Tape::standardize_cached`], mapping each tape to its standard form.
+///
+/// The keys don't include the rule, so a cache must only ever be used with a single rule. Every
+/// distinct input tape and its standard form are kept until the cache is dropped or cleared, so
+/// this trades memory for speed and only pays off when the same tapes are standardized repeatedly.
+pub type StandardizeCache = HashMap<Tape, Tape>;
+
+#[derive(Debug)]
 pub struct Tape {
     positive_values: Vec<Value>,
     negative_values: Vec<Value>,
@@ -34,6 +83,333 @@ impl Tape {
         }
     }
 
+    /// Constructs a tape directly from its internal storage: `positive_values[i]` is the digit at
+    /// index `i` and `negative_values[i]` is the digit at index `-(i + 1)`.
+    ///
+    /// This is the inverse of [`Tape::into_arrays`].
+    pub fn from_vecs(positive_values: Vec<Value>, negative_values: Vec<Value>) -> Self {
+        Tape {
+            positive_values,
+            negative_values,
+        }
+    }
+
+    /// Consumes the tape and returns `(positive_values, negative_values)` without cloning.
+    ///
+    /// Note that unlike the input to [`Tape::from_arrays`], the positive values are stored least
+    /// significant first, i.e. `positive_values[i]` is the digit at index `i`.
+    /// `negative_values[i]` is the digit at index `-(i + 1)`.
+    pub fn into_arrays(self) -> (Vec<Value>, Vec<Value>) {
+        (self.positive_values, self.negative_values)
+    }
+
+    /// The stored digits at nonnegative indices, least significant first, as in
+    /// [`Tape::into_arrays`].
+    pub fn positive_digits(&self) -> &[Value] {
+        &self.positive_values
+    }
+
+    /// The stored digits at negative indices, starting at index `-1`, as in
+    /// [`Tape::into_arrays`].
+    pub fn negative_digits(&self) -> &[Value] {
+        &self.negative_values
+    }
+
+    /// The `(index, value)` pairs of the populated range, most significant first, as yielded by
+    /// [`Tape::index_iter`] and [`Tape::iter`].
+    pub fn digits_be(&self) -> Vec<(isize, Value)> {
+        self.index_iter().zip(self.iter()).collect()
+    }
+
+    /// The `(index, value)` pairs of the populated range, least significant first.
+    pub fn digits_le(&self) -> Vec<(isize, Value)> {
+        let (min, _) = self.range();
+        (min..)
+            .zip(
+                self.negative_values
+                    .iter()
+                    .rev()
+                    .chain(self.positive_values.iter())
+                    .copied(),
+            )
+            .collect()
+    }
+
+    /// The greedy expansion of the nonnegative `value` with at most `max_places` digits at negative
+    /// indices.
+    ///
+    /// Digits are chosen with a small relative tolerance, so that values such as `2 = phi + phi^-2`
+    /// are not turned into long tails by rounding errors.
+    pub fn from_f64(value: f64, rule: &Rule, max_places: usize) -> Self {
+        Self::from_f64_tol(value, rule, 0., max_places).0
+    }
+
+    /// Like [`Tape::from_f64`], but stops as soon as the remaining value is below `tol`.
+    ///
+    /// Returns the expansion and the remaining value, i.e. the difference between `value` and the
+    /// value of the expansion, which is below `tol` unless `max_places` was reached first.
+    pub fn from_f64_tol(value: f64, rule: &Rule, tol: f64, max_places: usize) -> (Self, f64) {
+        assert!(value >= 0. && value.is_finite());
+        assert!(rule.base() > 1.);
+        let base = rule.base();
+        let mut result = Tape::zero();
+        if value == 0. {
+            return (result, 0.);
+        }
+        let mut index = (value.ln() / base.ln()).floor() as isize;
+        while base.powi(i32::try_from(index + 1).unwrap()) <= value * (1. + GREEDY_TOLERANCE) {
+            index += 1;
+        }
+        let min_index = -isize::try_from(max_places).unwrap();
+        let remainder = result.expand_greedily(rule, value, (min_index..=index.max(0)).rev(), tol);
+        (result, remainder)
+    }
+
+    /// Sets the digits at `indices`, which must be descending, to the greedy expansion of
+    /// `remainder`, stopping early once the remainder is below `tol`. Returns the final remainder.
+    fn expand_greedily(
+        &mut self,
+        rule: &Rule,
+        mut remainder: f64,
+        indices: impl Iterator<Item = isize>,
+        tol: f64,
+    ) -> f64 {
+        for index in indices {
+            if remainder <= 0. || remainder < tol {
+                break;
+            }
+            let weight = rule.base().powi(i32::try_from(index).unwrap());
+            let digit = (remainder / weight * (1. + GREEDY_TOLERANCE))
+                .floor()
+                .min(f64::from(rule.first()));
+            self[index] = digit as Value;
+            remainder = (remainder - digit * weight).max(0.);
+        }
+        remainder
+    }
+
+    /// Appends `additional_places` digits below the least significant digit, continuing the greedy
+    /// expansion of `value`.
+    ///
+    /// The tape should be standard and a prefix of the greedy expansion of `value`, such as the
+    /// result of [`Tape::from_f64`], for the result to be the longer greedy expansion.
+    pub fn refine(&mut self, value: f64, rule: &Rule, additional_places: usize) {
+        let remainder = (value - self.value(rule)).max(0.);
+        let (min, _) = self.range();
+        let additional_places = isize::try_from(additional_places).unwrap();
+        self.expand_greedily(rule, remainder, (min - additional_places..min).rev(), 0.);
+    }
+
+    /// Expands `value` in a quadratic base with `terms` digits at negative indices.
+    ///
+    /// This is [`Tape::from_f64`] restricted to rules of degree 2, the quadratic Pisot bases such
+    /// as phi, for which the expansions of elements of `Q(base)` are eventually periodic just like
+    /// the continued fractions of quadratic irrationals. Returns an error for other degrees.
+    pub fn from_cf(value: f64, rule: &Rule, terms: usize) -> Result<Self, DegreeError> {
+        if rule.degree() != 2 {
+            return Err(DegreeError {
+                expected: 2,
+                actual: rule.degree(),
+            });
+        }
+        Ok(Self::from_f64(value, rule, terms))
+    }
+
+    /// Converts signed digits, laid out as for [`Tape::from_arrays`], into the standard tape with
+    /// the same value. Panics if the value is negative.
+    ///
+    /// The positive and negative parts of the digits are standardized separately. Their
+    /// difference has a positive digit above every negative one, since standard tapes compare
+    /// lexicographically like their values. The highest negative digit is then resolved by
+    /// borrowing, i.e. applying the rule in reverse at the nearest positive digit above it,
+    /// until no negative digits remain.
+    pub fn from_balanced(
+        positives: &[SignedValue],
+        negatives: &[SignedValue],
+        rule: &Rule,
+    ) -> Self {
+        let signed_digits = || {
+            let positive_indices = (0..positives.len())
+                .rev()
+                .map(|i| isize::try_from(i).unwrap());
+            let negative_indices = (1..=negatives.len()).map(|i| -isize::try_from(i).unwrap());
+            positive_indices
+                .chain(negative_indices)
+                .zip(positives.iter().chain(negatives).copied())
+        };
+        let part = |sign: SignedValue| {
+            let mut tape = Tape::zero();
+            for (index, digit) in signed_digits() {
+                if digit.signum() == sign {
+                    tape[index] += Value::try_from(digit.unsigned_abs()).unwrap();
+                }
+            }
+            tape.normalize_in_place(rule)
+        };
+        let mut digits = part(1).to_signed_map();
+        for (index, value) in part(-1).to_signed_map() {
+            *digits.entry(index).or_default() -= value;
+        }
+        while let Some((&index, _)) = digits.iter().rev().find(|&(_, &value)| value < 0) {
+            let (&borrow_index, _) = digits
+                .range(index + 1..)
+                .find(|&(_, &value)| value > 0)
+                .expect("Balanced digits must have a nonnegative value.");
+            *digits.get_mut(&borrow_index).unwrap() -= 1;
+            for (rule_index, rule_value) in rule.iter().enumerate() {
+                let tape_index = borrow_index - isize::try_from(rule_index + 1).unwrap();
+                *digits.entry(tape_index).or_default() += SignedValue::from(rule_value);
+            }
+        }
+        Tape::try_from_signed_map(&digits)
+            .unwrap()
+            .normalize_in_place(rule)
+    }
+
+    /// Constructs a tape from `(index, value)` pairs. Repeated indices are summed.
+    pub fn from_sparse(digits: &[(isize, Value)]) -> Self {
+        let mut result = Tape::zero();
+        for &(index, value) in digits {
+            result[index] += value;
+        }
+        result
+    }
+
+    /// The `(index, value)` pairs of all nonzero digits in descending order of index.
+    pub fn to_sparse(&self) -> Vec<(isize, Value)> {
+        self.index_iter()
+            .zip(self.iter())
+            .filter(|&(_, value)| value != 0)
+            .collect()
+    }
+
+    /// Adds two sparse digit lists sorted in descending order of index, as returned by
+    /// [`Tape::to_sparse`], giving a list in the same format.
+    ///
+    /// This takes `O(n + m)` time in the number of entries, whereas adding the dense tapes takes
+    /// time proportional to their width, which is much larger for wide tapes with few nonzero
+    /// digits.
+    pub fn add_sparse(lhs: &[(isize, Value)], rhs: &[(isize, Value)]) -> Vec<(isize, Value)> {
+        lhs.iter()
+            .merge_join_by(rhs.iter(), |(lhs_index, _), (rhs_index, _)| {
+                rhs_index.cmp(lhs_index)
+            })
+            .map(|entry| match entry {
+                EitherOrBoth::Both(&(index, lhs_value), &(_, rhs_value)) => {
+                    (index, lhs_value + rhs_value)
+                }
+                EitherOrBoth::Left(&entry) | EitherOrBoth::Right(&entry) => entry,
+            })
+            .collect()
+    }
+
+    /// The nonzero digits as a map from index to value.
+    pub fn to_index_map(&self) -> BTreeMap<isize, Value> {
+        self.to_sparse().into_iter().collect()
+    }
+
+    /// The nonzero digits as a map from index to signed value, for arithmetic whose intermediate
+    /// results may go negative.
+    pub fn to_signed_map(&self) -> BTreeMap<isize, SignedValue> {
+        self.to_sparse()
+            .into_iter()
+            .map(|(index, value)| (index, SignedValue::from(value)))
+            .collect()
+    }
+
+    /// Converts signed digits back into a tape, or returns `None` if any digit is negative or too
+    /// large to be a digit.
+    ///
+    /// The tape itself never holds negative digits. Its own arithmetic, such as
+    /// [`Tape::apply_in_place`], checks digits before subtracting from them instead.
+    pub fn try_from_signed_map(map: &BTreeMap<isize, SignedValue>) -> Option<Self> {
+        let mut result = Tape::zero();
+        for (&index, &value) in map {
+            result[index] += Value::try_from(value).ok()?;
+        }
+        Some(result)
+    }
+
+    /// Adds the digits of a map as returned by [`Tape::to_index_map`] to the tape, growing it as
+    /// needed.
+    pub fn add_index_map(&mut self, map: &BTreeMap<isize, Value>) {
+        for (&index, &value) in map {
+            self[index] += value;
+        }
+    }
+
+    /// Packs the digits of the range, most significant first, into [`Rule::bits_per_digit`] bits
+    /// each, filling every byte from its least significant bit.
+    ///
+    /// The range is not stored, so [`Tape::range`] must be kept to [`Tape::unpack`] the digits.
+    /// Panics if the tape is not valid for the rule.
+    pub fn pack(&self, rule: &Rule) -> Vec<u8> {
+        assert!(self.is_valid(rule));
+        let bits = usize::try_from(rule.bits_per_digit()).unwrap();
+        let digit_count = self.positive_values.len() + self.negative_values.len();
+        let mut bytes = vec![0u8; (digit_count * bits).div_ceil(8)];
+        for (position, value) in self.iter().enumerate() {
+            for bit in 0..bits {
+                if value >> bit & 1 == 1 {
+                    let offset = position * bits + bit;
+                    bytes[offset / 8] |= 1 << (offset % 8);
+                }
+            }
+        }
+        bytes
+    }
+
+    /// The inverse of [`Tape::pack`], where `layout` is the [`Tape::range`] of the packed tape.
+    ///
+    /// Returns `None` if `layout` is not a range `(min, max)` with `min <= 0 <= max`, if `bytes` is
+    /// too short to hold its digits, or if a digit is above the first rule value.
+    pub fn unpack(bytes: &[u8], rule: &Rule, layout: (isize, isize)) -> Option<Self> {
+        let (min, max) = layout;
+        if min > 0 || max < 0 {
+            return None;
+        }
+        let bits = usize::try_from(rule.bits_per_digit()).unwrap();
+        let width = max.abs_diff(min);
+        if bytes.len() < width.checked_mul(bits)?.div_ceil(8) {
+            return None;
+        }
+        let mut result = Tape::from_vecs(vec![0; max.unsigned_abs()], vec![0; min.unsigned_abs()]);
+        for (position, index) in (min..max).rev().enumerate() {
+            result[index] = (0..bits)
+                .map(|bit| {
+                    let offset = position * bits + bit;
+                    Value::from(bytes[offset / 8] >> (offset % 8) & 1) << bit
+                })
+                .sum();
+        }
+        result.is_valid(rule).then_some(result)
+    }
+
+    /// Run-length encodes the populated range as `(digit, run length)` pairs, most significant
+    /// digit first.
+    ///
+    /// The runs do not record where index zero is, so to reconstruct the tape with
+    /// [`Tape::from_rle`] the number of digits at negative indices, `-self.range().0`, must be
+    /// stored alongside.
+    pub fn to_rle(&self) -> Vec<(Value, usize)> {
+        self.iter()
+            .dedup_with_count()
+            .map(|(count, value)| (value, count))
+            .collect()
+    }
+
+    /// Reconstructs a tape from runs as produced by [`Tape::to_rle`], where the last `negative_len`
+    /// digits are at negative indices.
+    pub fn from_rle(runs: &[(Value, usize)], negative_len: usize) -> Self {
+        let mut digits: Vec<_> = runs
+            .iter()
+            .flat_map(|&(value, count)| std::iter::repeat_n(value, count))
+            .collect();
+        assert!(negative_len <= digits.len());
+        let negative_values = digits.split_off(digits.len() - negative_len);
+        Self::from_arrays(digits, negative_values)
+    }
+
     pub fn zero() -> Self {
         Self {
             positive_values: vec![],
@@ -50,9 +426,47 @@ impl Tape {
 
     fn internal_index(index: isize) -> (bool, usize) {
         if index >= 0 {
-            (true, usize::try_from(index).unwrap())
+            (true, index.unsigned_abs())
         } else {
-            (false, usize::try_from(-index - 1).unwrap())
+            // `-index - 1` without overflowing at `isize::MIN`.
+            (false, (index + 1).unsigned_abs())
+        }
+    }
+
+    /// The digit at `index`, or `None` if `index` is outside [`Tape::range`].
+    ///
+    /// Unlike indexing, this distinguishes stored zeros from unpopulated positions.
+    pub fn get(&self, index: isize) -> Option<Value> {
+        match Self::internal_index(index) {
+            (true, index) => self.positive_values.get(index).copied(),
+            (false, index) => self.negative_values.get(index).copied(),
+        }
+    }
+
+    /// The digit at the nonnegative index `pos`, i.e. `self[pos]` without the cast to `isize`.
+    pub fn digit(&self, pos: usize) -> Value {
+        self.positive_values.get(pos).copied().unwrap_or(0)
+    }
+
+    /// The digit at fractional position `pos`, i.e. at index `-(pos + 1)`, so fractional position
+    /// 0 is the first digit after the comma.
+    pub fn frac_digit(&self, pos: usize) -> Value {
+        self.negative_values.get(pos).copied().unwrap_or(0)
+    }
+
+    /// The digits at indices `high` down to `low`, both inclusive, with unpopulated positions read
+    /// as zero.
+    pub fn digits_between(&self, high: isize, low: isize) -> Vec<Value> {
+        (low..=high).rev().map(|index| self[index]).collect()
+    }
+
+    /// Overwrites the digits starting at `start` and going down, so `digits[i]` ends up at index
+    /// `start - i`, growing the storage as needed.
+    ///
+    /// The digits are not checked, so the result may be invalid or non-standard.
+    pub fn set_range(&mut self, start: isize, digits: &[Value]) {
+        for (offset, &value) in digits.iter().enumerate() {
+            self[start - isize::try_from(offset).unwrap()] = value;
         }
     }
 
@@ -69,233 +483,2258 @@ impl Tape {
         (min..max).rev()
     }
 
-    pub fn value(&self, rule: &Rule) -> f64 {
-        self.iter()
-            .zip(self.index_iter())
-            .map(|(value, index)| {
-                f64::from(value) * rule.base().powi(i32::try_from(index).unwrap())
-            })
-            .sum::<f64>()
+    /// Folds over the `(index, value)` pairs of the populated range in descending order of index.
+    pub fn fold_digits<B>(&self, init: B, mut f: impl FnMut(B, isize, Value) -> B) -> B {
+        self.index_iter()
+            .zip(self.iter())
+            .fold(init, |acc, (index, value)| f(acc, index, value))
     }
 
-    pub fn apply(&self, rule: &Rule, index: isize) -> Result<Self, ApplyRuleError> {
-        self.clone().apply_in_place(rule, index)
+    /// A tape with every digit in the populated range replaced by `f(index, digit)`.
+    ///
+    /// The result need not be valid or standard.
+    pub fn map_digits(&self, f: impl Fn(isize, Value) -> Value) -> Tape {
+        let mut result = self.clone();
+        for index in self.index_iter() {
+            result[index] = f(index, self[index]);
+        }
+        result
     }
 
-    pub fn apply_in_place(mut self, rule: &Rule, index: isize) -> Result<Self, ApplyRuleError> {
-        let rule_len = rule.len() as isize;
-        assert!(rule_len > 0);
-        self[index] += 1;
-        for (rule_index, rule_value) in rule.iter().enumerate() {
-            let tape_index = index - isize::try_from(rule_index + 1).unwrap();
-            if self[tape_index] < rule_value {
-                return Err(ApplyRuleError {
-                    application_index: index,
-                    rule_index,
-                    rule_value,
-                    tape_value: self[tape_index],
-                });
-            }
-            self[tape_index] -= rule_value;
-        }
-        Ok(self)
+    /// Whether every digit is zero, i.e. the tape has value zero under any rule.
+    pub fn is_zero(&self) -> bool {
+        self.iter().all(|value| value == 0)
     }
 
-    pub fn is_valid(&self, rule: &Rule) -> bool {
-        let max_allowed = rule.first();
-        self.iter().all(|value| value <= max_allowed)
+    /// Sum of all digits.
+    pub fn digit_sum(&self) -> u64 {
+        self.iter().map(u64::from).sum()
     }
 
-    pub fn is_standard(&self, rule: &Rule) -> bool {
-        if !self.is_valid(rule) {
-            return false;
-        }
-        let rule_len = rule.len() as isize;
-        assert!(rule_len > 0);
-        let (min, max) = self.range();
-        let mut cur = max;
-        for (i, value) in self.index_iter().zip(self.iter()) {
-            let rule_index = usize::try_from(cur - i - 1).unwrap();
-            if let Some(rule_value) = rule.get(rule_index) {
-                if value < rule_value {
-                    cur = i;
+    /// Sum of all digits as a signed value.
+    pub fn signed_digit_sum(&self) -> SignedValue {
+        SignedValue::try_from(self.digit_sum()).unwrap()
+    }
+
+    fn longest_run(&self, predicate: impl Fn(Value) -> bool) -> usize {
+        self.iter()
+            .fold((0, 0), |(longest, current), value| {
+                if predicate(value) {
+                    (longest.max(current + 1), current + 1)
+                } else {
+                    (longest, 0)
                 }
-            } else {
-                println!("{} {}", cur, i);
-                return false;
-            }
-        }
-        if cur - min == rule_len {
-            return false;
-        }
-        assert!(cur - min < rule_len);
-        true
+            })
+            .0
     }
 
-    pub fn standardize(&self, rule: &Rule) -> Self {
-        self.clone().standardize_in_place(rule)
+    /// Length of the longest run of consecutive nonzero digits in the populated range.
+    pub fn longest_nonzero_run(&self) -> usize {
+        self.longest_run(|value| value != 0)
     }
 
-    pub fn standardize_in_place(mut self, rule: &Rule) -> Self {
-        assert!(self.is_valid(rule));
-        let rule_max = rule.first();
-        let rule_len = rule.len() as isize;
-        assert!(rule_len > 0);
-        let (min, max) = self.range();
+    /// Length of the longest run of consecutive zero digits in the populated range.
+    pub fn longest_zero_run(&self) -> usize {
+        self.longest_run(|value| value == 0)
+    }
 
-        let mut cur = max;
-        for i in self.index_iter() {
-            let rule_index = usize::try_from(cur - i - 1).unwrap();
-            if let Some(rule_value) = rule.get(rule_index) {
-                if self[i] < rule_value {
-                    cur = i;
-                }
-            } else {
-                assert!(self[cur] < rule_max);
-                self = self.apply_in_place(rule, cur).unwrap();
-                cur = i;
+    /// Number of occurrences of each digit in the populated range, indexed by digit.
+    pub fn digit_histogram(&self) -> Vec<usize> {
+        let mut histogram = vec![];
+        for value in self.iter() {
+            let value = usize::try_from(value).unwrap();
+            if histogram.len() <= value {
+                histogram.resize(value + 1, 0);
             }
+            histogram[value] += 1;
         }
-        if cur - min - 1 == rule_len {
-            self = self.apply_in_place(rule, cur).unwrap();
-        }
-        assert!(cur - min < rule_len);
-        self
+        histogram
     }
-}
 
-impl Display for Tape {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Some(first_positive) = self.positive_values.first() {
-            for &value in self.positive_values[1..].iter().rev() {
-                write!(f, "{value} ")?;
-            }
-            write!(f, "{first_positive}")?;
-        } else {
-            write!(f, "0")?;
-        }
-        if let Some(last_negative) = self.negative_values.last() {
-            write!(f, ",")?;
-            for &value in self.negative_values[..(self.negative_values.len() - 1)].iter() {
-                write!(f, "{value} ")?;
-            }
-            write!(f, "{last_negative}")?;
-        }
-        Ok(())
+    /// Shannon entropy in bits of the digit distribution in the populated range.
+    ///
+    /// Tapes with no populated digits or only one distinct digit have entropy 0.
+    pub fn digit_entropy(&self) -> f64 {
+        let histogram = self.digit_histogram();
+        let total = histogram.iter().sum::<usize>() as f64;
+        histogram
+            .into_iter()
+            .filter(|&count| count > 0)
+            .map(|count| {
+                let probability = count as f64 / total;
+                -probability * probability.log2()
+            })
+            .sum::<f64>()
+            .max(0.)
     }
-}
 
-impl PartialEq<Tape> for Tape {
-    fn eq(&self, other: &Tape) -> bool {
-        let min_pos_len = self.positive_values.len().min(other.positive_values.len());
-        let min_neg_len = self.negative_values.len().min(other.negative_values.len());
-        self.positive_values[..min_pos_len] == other.positive_values[..min_pos_len]
-            && self.positive_values[min_pos_len..]
-                .iter()
-                .chain(other.positive_values[min_pos_len..].iter())
-                .all(|&x| x == 0)
-            && self.negative_values[..min_neg_len] == other.negative_values[..min_neg_len]
-            && self.negative_values[min_neg_len..]
-                .iter()
-                .chain(other.negative_values[min_neg_len..].iter())
-                .all(|&x| x == 0)
+    /// Number of most significant positions on which the tapes agree, starting from the highest
+    /// populated index of either tape and treating unpopulated positions as zero.
+    ///
+    /// If the tapes are equal, this is the length of the union of their ranges.
+    pub fn common_prefix_len(&self, other: &Tape) -> usize {
+        self.union_index_iter(other)
+            .take_while(|&index| self[index] == other[index])
+            .count()
     }
-}
 
-impl Eq for Tape {}
+    /// The indices where the tapes differ together with the digits of `self` and `other` there,
+    /// in descending order of index. Unpopulated positions are treated as zero.
+    pub fn diff(&self, other: &Tape) -> Vec<(isize, Value, Value)> {
+        self.union_index_iter(other)
+            .map(|index| (index, self[index], other[index]))
+            .filter(|&(_, self_value, other_value)| self_value != other_value)
+            .collect()
+    }
 
-impl Index<isize> for Tape {
-    type Output = Value;
+    /// The sum of the absolute digit differences over the union of the ranges, with unpopulated
+    /// positions treated as zero.
+    ///
+    /// Like [`Tape::diff`], this compares representations rather than values, so tapes are
+    /// usually standardized first.
+    pub fn l1_distance(&self, other: &Tape) -> u64 {
+        self.union_index_iter(other)
+            .map(|index| u64::from(self[index].abs_diff(other[index])))
+            .sum()
+    }
 
-    fn index(&self, index: isize) -> &Self::Output {
-        match Self::internal_index(index) {
-            (true, index) => self.positive_values.get(index).unwrap_or(&0),
-            (false, index) => self.negative_values.get(index).unwrap_or(&0),
-        }
+    /// The largest absolute digit difference over the union of the ranges, with unpopulated
+    /// positions treated as zero. See [`Tape::l1_distance`].
+    pub fn linf_distance(&self, other: &Tape) -> Value {
+        self.union_index_iter(other)
+            .map(|index| self[index].abs_diff(other[index]))
+            .max()
+            .unwrap_or(0)
     }
-}
 
-impl IndexMut<isize> for Tape {
-    fn index_mut(&mut self, index: isize) -> &mut Self::Output {
-        let (positive, index) = Self::internal_index(index);
-        let array = if positive {
-            &mut self.positive_values
-        } else {
-            &mut self.negative_values
-        };
-        if array.len() <= index {
-            array.resize(index + 1, 0);
-        }
-        array.get_mut(index).unwrap()
+    /// Indices in the union of the ranges of `self` and `other` in descending order.
+    fn union_index_iter(&self, other: &Tape) -> impl Iterator<Item = isize> {
+        let (self_min, self_max) = self.range();
+        let (other_min, other_max) = other.range();
+        (self_min.min(other_min)..self_max.max(other_max)).rev()
     }
-}
 
-impl AddAssign<Tape> for Tape {
-    fn add_assign(&mut self, rhs: Tape) {
-        for (self_array, rhs_array) in [
-            (&mut self.positive_values, &rhs.positive_values),
-            (&mut self.negative_values, &rhs.negative_values),
+    /// The smallest period of the digits at negative indices, read from index `-1` downwards.
+    ///
+    /// A period `p` is only reported if the populated negative range holds at least two full
+    /// repetitions, i.e. `p <= len / 2`, and `None` is returned if there is no such period.
+    pub fn negative_period(&self) -> Option<usize> {
+        let digits = &self.negative_values;
+        (1..=digits.len() / 2).find(|&period| {
+            digits
+                .iter()
+                .zip(digits[period..].iter())
+                .all(|(a, b)| a == b)
+        })
+    }
+
+    /// Adds the tapes digit by digit, returning `None` if any digit sum overflows [`Value`].
+    pub fn checked_add(&self, other: &Tape) -> Option<Tape> {
+        let mut result = self.clone();
+        for (result_array, other_array) in [
+            (&mut result.positive_values, &other.positive_values),
+            (&mut result.negative_values, &other.negative_values),
         ] {
-            if rhs_array.len() > self_array.len() {
-                self_array.resize(rhs_array.len(), 0);
+            if other_array.len() > result_array.len() {
+                result_array.resize(other_array.len(), 0);
             }
-            for (value, &rhs_value) in self_array.iter_mut().zip(rhs_array.iter()) {
-                *value += rhs_value
+            for (value, &other_value) in result_array.iter_mut().zip(other_array.iter()) {
+                *value = value.checked_add(other_value)?;
             }
         }
+        Some(result)
     }
-}
-
-impl Add<Tape> for Tape {
-    type Output = Tape;
 
-    fn add(mut self, rhs: Tape) -> Self::Output {
-        self += rhs;
-        self
+    /// Inserts `value` at `index`, moving the digit at `index` and every digit below it down by one
+    /// position.
+    ///
+    /// If `high` is the value of the digits above `index` and `low` that of the digits at or below
+    /// it, the value of the tape changes from `high + low` to
+    /// `high + value * base^index + low / base`. The tape stays valid if `value` is, but it may no
+    /// longer be standard.
+    pub fn insert_digit(&mut self, rule: &Rule, index: isize, value: Value) {
+        assert!(value <= rule.first());
+        let (min, _) = self.range();
+        for i in min..=index {
+            self[i - 1] = self[i];
+        }
+        self[index] = value;
     }
-}
 
-#[cfg(test)]
-mod test {
-    use approx::assert_relative_eq;
-    use proptest::prelude::*;
+    pub fn value(&self, rule: &Rule) -> f64 {
+        value_from_digits(self.index_iter().zip(self.iter()), rule)
+    }
 
-    use super::*;
+    /// [`Tape::value`] together with a bound on its absolute error.
+    ///
+    /// The bound covers the uncertainty in the base, see [`Rule::base_error_bound`], as well as the
+    /// rounding in the powers, the products with the digits and the sum. A computation of `k`
+    /// floating point operations has relative error at most `k * u / (1 - k * u)`, where `u` is the
+    /// unit roundoff, and every term of the sum is positive. The bound is inflated slightly to
+    /// absorb the rounding in computing the bound itself.
+    pub fn value_with_error(&self, rule: &Rule) -> (f64, f64) {
+        let value = self.value(rule);
+        let base_error = rule.base_error_bound();
+        let unit_roundoff = f64::EPSILON / 2.;
+        let gamma =
+            |operations: f64| operations * unit_roundoff / (1. - operations * unit_roundoff);
+        let contributions = self.value_contributions(rule);
+        let additions = contributions.len() as f64;
+        let error: f64 = contributions
+            .iter()
+            .map(|&(index, contribution)| {
+                let exponent = index.unsigned_abs() as f64;
+                // `(1 - base_error)^-exponent - 1` bounds the relative error of `base^index`.
+                let base_term = (-exponent * (-base_error).ln_1p()).exp_m1();
+                // At most `exponent` operations for the power, plus a reciprocal for negative
+                // indices and the product with the digit.
+                let rounding_term = gamma(exponent + 2. + additions);
+                contribution * (base_term + rounding_term) * (1. + base_term)
+            })
+            .sum();
+        (value, error * (1. + gamma(2. * additions + 8.)))
+    }
 
-    #[test]
-    fn from_arrays() {
-        let x = Tape::from_arrays([1, 2, 3], [4, 5, 6]);
-        assert_eq!(x.positive_values, vec![3, 2, 1]);
-        assert_eq!(x.negative_values, vec![4, 5, 6]);
+    /// [`Tape::value`] computed with Kahan summation.
+    ///
+    /// The naive sum loses every contribution below the rounding error of the running total, so
+    /// for tapes spanning many orders of magnitude the error can grow with the number of digits.
+    /// Compensated summation keeps track of the lost low-order parts, bounding the error by a few
+    /// ulps of the result independently of the number of digits.
+    pub fn value_kahan(&self, rule: &Rule) -> f64 {
+        let (sum, compensation) = self.index_iter().zip(self.iter()).fold(
+            (0., 0.),
+            |(sum, compensation): (f64, f64), (index, value)| {
+                let term = f64::from(value) * rule.base().powi(i32::try_from(index).unwrap());
+                let term = term - compensation;
+                let next = sum + term;
+                (next, (next - sum) - term)
+            },
+        );
+        sum - compensation
     }
 
-    #[test]
-    fn eq() {
-        let x = Tape::from_arrays([0, 1, 2, 3], [4, 5, 6]);
-        let y = Tape::from_arrays([1, 2, 3], [4, 5, 6, 0]);
-        assert_eq!(x, y);
+    /// The value of the digits at nonnegative indices, without splitting off a separate tape.
+    pub fn integer_value(&self, rule: &Rule) -> f64 {
+        value_from_digits((0..).zip(self.positive_values.iter().copied()), rule)
     }
 
-    #[test]
-    fn add() {
-        let x = Tape::from_arrays([1, 2], [3, 4, 5, 6]);
-        let y = Tape::from_arrays([1, 2, 3, 4], [5]);
-        let z = x + y;
-        assert_eq!(z.positive_values, vec![6, 4, 2, 1]);
-        assert_eq!(z.negative_values, vec![8, 4, 5, 6]);
-        assert_eq!(z, Tape::from_arrays([1, 2, 4, 6], [8, 4, 5, 6]));
+    /// The value of the digits at negative indices, so that
+    /// `integer_value + fractional_value == value` up to rounding.
+    pub fn fractional_value(&self, rule: &Rule) -> f64 {
+        value_from_digits(
+            (1..)
+                .map(|i: isize| -i)
+                .zip(self.negative_values.iter().copied()),
+            rule,
+        )
     }
 
-    #[test]
-    fn is_valid() {
-        let rule = Rule::from_array([1, 1]).unwrap();
-        assert!(!Tape::from_arrays([1, 2], [3, 4]).is_valid(&rule));
-        assert!(Tape::from_arrays([1, 1], [1, 1]).is_valid(&rule));
-        assert!(Tape::from_arrays([1, 0], [1, 0]).is_valid(&rule));
+    /// The index and contribution `digit * base^index` to the value of every nonzero digit, in
+    /// descending order of index. The contributions sum to [`Tape::value`].
+    pub fn value_contributions(&self, rule: &Rule) -> Vec<(isize, f64)> {
+        self.index_iter()
+            .zip(self.iter())
+            .filter(|&(_, value)| value != 0)
+            .map(|(index, value)| {
+                (
+                    index,
+                    f64::from(value) * rule.base().powi(i32::try_from(index).unwrap()),
+                )
+            })
+            .collect()
     }
 
-    #[test]
-    fn is_standard() {
+    /// Renders the tape as a LaTeX sum of powers of `\beta`, followed by its approximate value,
+    /// e.g. `2 \beta^{1} + \beta^{-2} \approx 3.618` for `2 0,0 1` in base phi.
+    ///
+    /// Only nonzero digits appear in the sum, and digits equal to one are left out as factors.
+    pub fn to_latex(&self, rule: &Rule) -> String {
+        let contributions = self.value_contributions(rule);
+        let sum = if contributions.is_empty() {
+            "0".to_string()
+        } else {
+            contributions
+                .iter()
+                .map(|&(index, _)| match self[index] {
+                    1 => format!("\\beta^{{{index}}}"),
+                    digit => format!("{digit} \\beta^{{{index}}}"),
+                })
+                .join(" + ")
+        };
+        let value = contributions
+            .iter()
+            .fold(0., |acc, &(_, contribution)| acc + contribution);
+        format!("{sum} \\approx {value}")
+    }
+
+    /// Computes the value of the tape modulo `modulus` exactly.
+    ///
+    /// Returns `None` if `modulus` is zero, the base of the rule is not an integer or the tape has
+    /// a nonzero digit at a negative index.
+    pub fn value_mod(&self, rule: &Rule, modulus: u64) -> Option<u64> {
+        if modulus == 0 {
+            return None;
+        }
+        let base = u128::from(rule.integer_base()?);
+        if self.negative_values.iter().any(|&value| value != 0) {
+            return None;
+        }
+        let modulus = u128::from(modulus);
+        let result = self
+            .positive_values
+            .iter()
+            .rev()
+            .fold(0, |acc, &value| (acc * base + u128::from(value)) % modulus);
+        Some(u64::try_from(result).unwrap())
+    }
+
+    /// An exact, order-preserving byte key for the value of the tape, for use as e.g. a
+    /// `BTreeMap` key.
+    ///
+    /// The key is the big-endian byte encoding of the value without leading zero bytes, prefixed
+    /// by its length as a big-endian `u64`, so that comparing keys compares values. Like
+    /// [`Tape::value_mod`], it returns `None` if the base is not an integer or the tape has a
+    /// nonzero digit at a negative index.
+    pub fn integer_sort_key(&self, rule: &Rule) -> Option<Vec<u8>> {
+        let base = u64::from(rule.integer_base()?);
+        if self.negative_values.iter().any(|&value| value != 0) {
+            return None;
+        }
+        // Little-endian bytes of the value, built most significant digit first.
+        let mut bytes: Vec<u8> = Vec::new();
+        for &value in self.positive_values.iter().rev() {
+            let mut carry = u64::from(value);
+            for byte in bytes.iter_mut() {
+                let product = u64::from(*byte) * base + carry;
+                *byte = (product & 0xff) as u8;
+                carry = product >> 8;
+            }
+            while carry > 0 {
+                bytes.push((carry & 0xff) as u8);
+                carry >>= 8;
+            }
+        }
+        while bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        let mut key = u64::try_from(bytes.len()).unwrap().to_be_bytes().to_vec();
+        key.extend(bytes.iter().rev());
+        Some(key)
+    }
+
+    /// Combines every `group` consecutive digits into a single digit, giving a tape in base
+    /// `base^group`.
+    ///
+    /// The base of `rule` must be an integer `n`, and the result is then a tape for the rule
+    /// `[n^group]` with the same value. The digit at index `i` ends up in the digit at index
+    /// `i.div_euclid(group)` with weight `n^i.rem_euclid(group)`.
+    ///
+    /// Returns `None` if a digit of the result doesn't fit in a [`Value`], e.g. when regrouping
+    /// eleven nonzero decimal digits at once. Panics if `group` is zero or the base of `rule` is
+    /// not an integer.
+    pub fn regroup(&self, group: usize, rule: &Rule) -> Option<Tape> {
+        assert!(group > 0);
+        let base = rule
+            .integer_base()
+            .expect("Only integer bases can be regrouped.");
+        let group = isize::try_from(group).unwrap();
+        let mut result = Tape::zero();
+        for (index, value) in self.index_iter().zip(self.iter()) {
+            if value == 0 {
+                continue;
+            }
+            let weight = base.checked_pow(u32::try_from(index.rem_euclid(group)).ok()?)?;
+            let digit = &mut result[index.div_euclid(group)];
+            *digit = digit.checked_add(value.checked_mul(weight)?)?;
+        }
+        Some(result)
+    }
+
+    /// The inverse of [`Tape::regroup`]: splits every digit of a tape in base `n^group` into
+    /// `group` digits in base `n`, where `n` is the integer base of `rule`.
+    ///
+    /// Digits of at least `n^group` spill over into the next group, so the value is preserved
+    /// regardless.
+    pub fn ungroup(&self, group: usize, rule: &Rule) -> Tape {
+        assert!(group > 0);
+        let base = rule
+            .integer_base()
+            .expect("Only integer bases can be regrouped.");
+        assert!(base > 1);
+        let group = isize::try_from(group).unwrap();
+        let mut result = Tape::zero();
+        for (index, mut value) in self.index_iter().zip(self.iter()) {
+            let mut result_index = index * group;
+            while value > 0 {
+                result[result_index] += value % base;
+                value /= base;
+                result_index += 1;
+            }
+        }
+        result
+    }
+
+    /// The [`Tape::range`] the tape will have after successfully applying the rule at `index`.
+    ///
+    /// Applying the rule touches the digits from `index` down to `index - rule.len()`, and the
+    /// storage grows to cover all of them, even when the application carries into positions
+    /// outside the current range.
+    pub fn predict_apply_range(&self, rule: &Rule, index: isize) -> (isize, isize) {
+        let (min, max) = self.range();
+        let lowest = index.saturating_sub_unsigned(rule.len());
+        let min = if lowest < 0 { min.min(lowest) } else { min };
+        let max = if index >= 0 { max.max(index + 1) } else { max };
+        (min, max)
+    }
+
+    /// Applies the rule at `index`, i.e. adds one at `index` and subtracts the rule values from the
+    /// digits below it. This preserves the value of the tape.
+    ///
+    /// The range of the tape grows to include all touched digits, see
+    /// [`Tape::predict_apply_range`].
+    pub fn apply(&self, rule: &Rule, index: isize) -> Result<Self, ApplyRuleError> {
+        self.clone().apply_in_place(rule, index)
+    }
+
+    pub fn apply_in_place(self, rule: &Rule, index: isize) -> Result<Self, ApplyRuleError> {
+        self.apply_with_slice(rule.values(), index)
+    }
+
+    /// [`Tape::apply_in_place`] taking the rule values directly, for hot loops that apply the same
+    /// rule many times. Callers must pass `rule.values()` of a valid [`Rule`].
+    ///
+    /// The digits below `index` are checked before anything is changed, so a failed application
+    /// at an index far outside the tape doesn't grow the storage. An application reaching below
+    /// `isize::MIN` fails with [`ApplyRuleError::IndexOverflow`].
+    pub fn apply_with_slice(
+        mut self,
+        rule_values: &[Value],
+        index: isize,
+    ) -> Result<Self, ApplyRuleError> {
+        assert!(!rule_values.is_empty());
+        let offset = rule_values.len();
+        let lowest = index
+            .checked_sub_unsigned(offset)
+            .ok_or(IndexOverflow { index, offset })?;
+        let window = || (lowest..index).rev().zip(rule_values.iter().copied());
+        for (rule_index, (tape_index, rule_value)) in window().enumerate() {
+            let tape_value = self[tape_index];
+            if tape_value < rule_value {
+                return Err(ApplyRuleError::InsufficientDigit {
+                    application_index: index,
+                    rule_index,
+                    rule_value,
+                    tape_value,
+                });
+            }
+        }
+        self[index] += 1;
+        for (tape_index, rule_value) in window() {
+            self[tape_index] -= rule_value;
+        }
+        Ok(self)
+    }
+
+    /// Whether [`Tape::apply`] would succeed at `index`, i.e. whether every digit below `index` is
+    /// at least the corresponding rule value, checked without cloning the tape.
+    pub fn can_apply(&self, rule: &Rule, index: isize) -> bool {
+        rule.iter().enumerate().all(|(rule_index, rule_value)| {
+            index
+                .checked_sub_unsigned(rule_index + 1)
+                .is_some_and(|tape_index| self[tape_index] >= rule_value)
+        })
+    }
+
+    /// Every index at which [`Tape::can_apply`] holds, in descending order.
+    ///
+    /// The rule values are positive, so only indices directly above a stored digit can qualify.
+    pub fn applicable_indices(&self, rule: &Rule) -> Vec<isize> {
+        self.index_iter()
+            .map(|index| index + 1)
+            .filter(|&index| self.can_apply(rule, index))
+            .collect()
+    }
+
+    pub fn saturating_apply(
+        &self,
+        rule: &Rule,
+        index: isize,
+    ) -> Result<(Self, Value), IndexOverflow> {
+        self.clone().saturating_apply_in_place(rule, index)
+    }
+
+    /// Applies the rule at `index`, subtracting as much as possible from digits that are smaller
+    /// than the corresponding rule value instead of failing.
+    ///
+    /// Returns the resulting tape and the total amount that could not be subtracted.
+    /// Unless the deficit is zero, this does *not* preserve the value of the tape.
+    /// Fails without changing anything if the rule would reach below `isize::MIN`.
+    pub fn saturating_apply_in_place(
+        mut self,
+        rule: &Rule,
+        index: isize,
+    ) -> Result<(Self, Value), IndexOverflow> {
+        assert!(!rule.is_empty());
+        let offset = rule.len();
+        let lowest = index
+            .checked_sub_unsigned(offset)
+            .ok_or(IndexOverflow { index, offset })?;
+        self[index] += 1;
+        let mut deficit = 0;
+        for (tape_index, rule_value) in (lowest..index).rev().zip(rule.iter()) {
+            let tape_value = self[tape_index];
+            deficit += rule_value.saturating_sub(tape_value);
+            self[tape_index] = tape_value.saturating_sub(rule_value);
+        }
+        Ok((self, deficit))
+    }
+
+    /// Applies the rule at each of `indices` in turn, all or nothing.
+    ///
+    /// The applications are done on a copy, so `self` is untouched either way. On failure, returns
+    /// the position in `indices` of the application that failed together with its error.
+    pub fn apply_transaction(
+        &self,
+        rule: &Rule,
+        indices: &[isize],
+    ) -> Result<Self, (usize, ApplyRuleError)> {
+        indices
+            .iter()
+            .enumerate()
+            .try_fold(self.clone(), |tape, (step, &index)| {
+                tape.apply_in_place(rule, index)
+                    .map_err(|error| (step, error))
+            })
+    }
+
+    /// Carries the digit at `index` into `index + 1` by applying the rule there until the digit is
+    /// below the first rule value, which for a first rule value of one means zero. Returns the
+    /// number of applications.
+    ///
+    /// Each application preserves the value of the tape. If an application fails because a digit
+    /// further down is too small, the error is returned and the tape is left as it was after the
+    /// last successful application.
+    pub fn zero_out_digit(&mut self, rule: &Rule, index: isize) -> Result<usize, ApplyRuleError> {
+        let mut applications = 0;
+        while self[index] >= rule.first() {
+            *self = self.apply(rule, index + 1)?;
+            applications += 1;
+        }
+        Ok(applications)
+    }
+
+    pub fn is_valid(&self, rule: &Rule) -> bool {
+        // Checking a whole chunk without branching vectorizes, while checking chunk by chunk still
+        // stops early on long invalid tapes.
+        const CHUNK: usize = 64;
+        let max_allowed = rule.first();
+        [&self.positive_values, &self.negative_values]
+            .into_iter()
+            .flat_map(|values| values.chunks(CHUNK))
+            .all(|chunk| {
+                !chunk
+                    .iter()
+                    .fold(false, |invalid, &value| invalid | (value > max_allowed))
+            })
+    }
+
+    /// Like [`Tape::is_valid`], but returns the index of the most significant digit above the
+    /// first rule value if there is one. See [`Rule::check_digits`] for raw digit slices.
+    pub fn validate(&self, rule: &Rule) -> Result<(), isize> {
+        let max_allowed = rule.first();
+        match self
+            .index_iter()
+            .zip(self.iter())
+            .find(|&(_, value)| value > max_allowed)
+        {
+            Some((index, _)) => Err(index),
+            None => Ok(()),
+        }
+    }
+
+    fn unapply_in_place(mut self, rule: &Rule, index: isize) -> Self {
+        assert!(self[index] > 0);
+        self[index] -= 1;
+        let lowest = index
+            .checked_sub_unsigned(rule.len())
+            .expect("Reverse applications stay near the stored digits.");
+        for (tape_index, rule_value) in (lowest..index).rev().zip(rule.iter()) {
+            self[tape_index] += rule_value;
+        }
+        self
+    }
+
+    /// Returns the highest index at which a window of `rule.len()` digits is lexicographically
+    /// greater than or equal to the rule values.
+    /// A tape is standard exactly when there is no such index.
+    fn highest_violation(&self, rule: &Rule) -> Option<isize> {
+        self.index_iter().find(|&index| {
+            for (rule_index, rule_value) in rule.iter().enumerate() {
+                // Positions below `isize::MIN` can't hold digits, so they read as zero.
+                let value = index
+                    .checked_sub_unsigned(rule_index)
+                    .map_or(0, |tape_index| self[tape_index]);
+                if value != rule_value {
+                    return value > rule_value;
+                }
+            }
+            true
+        })
+    }
+
+    /// Removes the violation starting at `index` by applying the rule at `index + 1`.
+    ///
+    /// If some digit in the window is too small for the rule to be applied directly, the rule is
+    /// first applied in reverse at the first digit that exceeds its rule value. Since the rule
+    /// values are non-increasing, this always makes the application possible.
+    ///
+    /// Carries only touch digits within two rule lengths of the stored ones, so the indices can't
+    /// overflow.
+    fn carry_in_place(mut self, rule: &Rule, index: isize) -> Self {
+        if let Some(reverse_index) = self.carry_reverse_index(rule, index) {
+            self = self.unapply_in_place(rule, reverse_index);
+        }
+        self.apply_in_place(rule, index + 1).unwrap()
+    }
+
+    /// The index at which [`Tape::carry_in_place`] applies the rule in reverse before removing the
+    /// violation starting at `index`, or `None` if the rule can be applied directly.
+    fn carry_reverse_index(&self, rule: &Rule, index: isize) -> Option<isize> {
+        let window_index = |rule_index: usize| {
+            index
+                .checked_sub_unsigned(rule_index)
+                .expect("Carries stay near the stored digits.")
+        };
+        let offset = (0..rule.len()).find(|&i| self[window_index(i)] != rule[i])?;
+        (offset..rule.len())
+            .any(|i| self[window_index(i)] < rule[i])
+            .then(|| window_index(offset))
+    }
+
+    /// The number of rule applications, one or two, of the carry removing the violation starting
+    /// at `index`.
+    fn carry_applications(&self, rule: &Rule, index: isize) -> usize {
+        1 + usize::from(self.carry_reverse_index(rule, index).is_some())
+    }
+
+    fn normalize_in_place(self, rule: &Rule) -> Self {
+        self.normalize_with_callback(rule, |_, _| {})
+    }
+
+    /// Normalizes the tape, calling `on_apply` with the tape and the index of the application
+    /// before each carry.
+    fn normalize_with_callback(
+        mut self,
+        rule: &Rule,
+        mut on_apply: impl FnMut(&Tape, isize),
+    ) -> Self {
+        while let Some(index) = self.highest_violation(rule) {
+            assert!(
+                rule.len() > 1 || rule.first() > 1,
+                "Only the zero tape is standard in base one."
+            );
+            on_apply(&self, index + 1);
+            self = self.carry_in_place(rule, index);
+        }
+        self
+    }
+
+    pub fn is_standard(&self, rule: &Rule) -> bool {
+        self.highest_violation(rule).is_none()
+    }
+
+    /// Lowest and highest index holding a nonzero digit, or `None` for a tape with value zero.
+    fn nonzero_range(&self) -> Option<(isize, isize)> {
+        let mut nonzero_indices = self
+            .index_iter()
+            .zip(self.iter())
+            .filter(|&(_, value)| value != 0)
+            .map(|(index, _)| index);
+        let highest = nonzero_indices.next()?;
+        Some((nonzero_indices.last().unwrap_or(highest), highest))
+    }
+
+    /// Whether the nonzero digits of the tape span no more positions than those of its standard
+    /// form.
+    ///
+    /// A standard tape is trivially minimal, while e.g. a tape with a carry left to do at the top
+    /// is not. Note that some non-standard tapes are shorter than their standard form, e.g.
+    /// `1 1 1` and `1 0 0 1` in base phi, and these are also considered minimal.
+    pub fn is_minimal_length(&self, rule: &Rule) -> bool {
+        let standard = self.clone().normalize_in_place(rule);
+        let extent = |tape: &Tape| {
+            tape.nonzero_range()
+                .map_or(0, |(lowest, highest)| highest - lowest + 1)
+        };
+        extent(self) <= extent(&standard)
+    }
+
+    /// Formats the tape like [`Display`], but without leading zeros in the integer part or
+    /// trailing zeros in the fractional part. At least one integer digit is kept, and the comma
+    /// is dropped when no fractional digits remain.
+    pub fn display_trimmed(&self) -> String {
+        let integer_len = self
+            .positive_values
+            .iter()
+            .rposition(|&value| value != 0)
+            .map_or(1, |position| position + 1);
+        let fraction_len = self
+            .negative_values
+            .iter()
+            .rposition(|&value| value != 0)
+            .map_or(0, |position| position + 1);
+        let mut result = (0..integer_len)
+            .rev()
+            .map(|i| self.positive_values.get(i).copied().unwrap_or(0))
+            .join(" ");
+        if fraction_len > 0 {
+            result.push(',');
+            result.push_str(&self.negative_values[..fraction_len].iter().join(" "));
+        }
+        result
+    }
+
+    /// A multi-line summary of the tape under `rule`, meant for interactive exploration.
+    pub fn describe(&self, rule: &Rule) -> String {
+        let (min, max) = self.range();
+        format!(
+            "tape: {self}\nrange: {min}..{max}\nvalue: {}\nvalid: {}\nstandard: {}\nbase: {}",
+            self.value(rule),
+            self.is_valid(rule),
+            self.is_standard(rule),
+            rule.base()
+        )
+    }
+
+    pub fn standardize(&self, rule: &Rule) -> Self {
+        self.clone().standardize_in_place(rule)
+    }
+
+    /// Encodes a pair of tapes in one by placing the digit of `a` at index `i` at index `2 * i` and
+    /// the digit of `b` at index `i` at index `2 * i + 1`. [`Tape::deinterleave`] reverses this.
+    ///
+    /// If `a` and `b` are tapes in base `beta`, the result read in base `sqrt(beta)` has the value
+    /// `value(a) + sqrt(beta) * value(b)`. That base generally has no [`Rule`] of its own, so the
+    /// interleaved tape should only be decoded, not standardized.
+    pub fn interleave(a: &Tape, b: &Tape) -> Tape {
+        let mut result = Tape::zero();
+        for (index, value) in a.to_sparse() {
+            result[2 * index] = value;
+        }
+        for (index, value) in b.to_sparse() {
+            result[2 * index + 1] = value;
+        }
+        result
+    }
+
+    /// Splits a tape produced by [`Tape::interleave`] into the tapes of its even and odd indices.
+    pub fn deinterleave(&self) -> (Tape, Tape) {
+        let mut even = Tape::zero();
+        let mut odd = Tape::zero();
+        for (index, value) in self.to_sparse() {
+            if index.rem_euclid(2) == 0 {
+                even[index.div_euclid(2)] = value;
+            } else {
+                odd[index.div_euclid(2)] = value;
+            }
+        }
+        (even, odd)
+    }
+
+    /// Moves every digit from index `i` to index `i + k`, multiplying the value by `base^k`.
+    pub fn shift(&self, k: isize) -> Self {
+        let (min, max) = self.range();
+        let mut result = Tape::from_vecs(
+            vec![0; (max + k).max(0).unsigned_abs()],
+            vec![0; (-(min + k)).max(0).unsigned_abs()],
+        );
+        for (index, value) in self.index_iter().zip(self.iter()) {
+            result[index + k] = value;
+        }
+        result
+    }
+
+    /// The standard form of `self.shift(k)`.
+    ///
+    /// Shifting doesn't change the digit string, so a shift of a standard tape is standard and is
+    /// returned without standardizing again. This holds for negative `k` as well as positive. Only
+    /// non-standard inputs are shifted and then standardized.
+    pub fn shift_standard(&self, k: isize, rule: &Rule) -> Self {
+        if self.is_standard(rule) {
+            self.shift(k)
+        } else {
+            self.shift(k).standardize(rule)
+        }
+    }
+
+    /// Like [`Tape::standardize`], but looks the tape up in `cache` first and stores the result
+    /// there on a miss.
+    ///
+    /// `cache` must only have been filled with `rule`. See [`StandardizeCache`].
+    pub fn standardize_cached(&self, rule: &Rule, cache: &mut StandardizeCache) -> Self {
+        if let Some(standard) = cache.get(self) {
+            return standard.clone();
+        }
+        let standard = self.standardize(rule);
+        cache.insert(self.clone(), standard.clone());
+        standard
+    }
+
+    /// Whether [`Tape::standardize`] would complete without panicking.
+    ///
+    /// The tape must be valid, and in base one only the already standard zero tape can be
+    /// standardized. Defensive callers can check this before standardizing.
+    pub fn can_standardize(&self, rule: &Rule) -> bool {
+        self.is_valid(rule) && (rule.len() > 1 || rule.first() > 1 || self.is_standard(rule))
+    }
+
+    pub fn standardize_in_place(self, rule: &Rule) -> Self {
+        assert!(self.is_valid(rule));
+        self.normalize_in_place(rule)
+    }
+
+    /// The standard form of the tape with no zeros stored above its highest or below its lowest
+    /// nonzero digit.
+    ///
+    /// `==` already ignores such zeros, but the canonical tapes of equal values also have
+    /// identical storage, so e.g. [`Tape::positive_digits`] and [`Tape::range`] agree too.
+    pub fn canonicalize(&self, rule: &Rule) -> Self {
+        let mut tape = self.standardize(rule);
+        for values in [&mut tape.positive_values, &mut tape.negative_values] {
+            let len = values
+                .iter()
+                .rposition(|&value| value != 0)
+                .map_or(0, |position| position + 1);
+            values.truncate(len);
+        }
+        tape
+    }
+
+    /// The largest standard tape with no digits below the lowest index of the tape's range whose
+    /// value is at most that of the tape, i.e. the tape rounded down to its own resolution.
+    ///
+    /// The tape may be non-standard or even invalid. Its exact standard form can have digits
+    /// further down, which are simply cut off since truncating a standard tape keeps it standard
+    /// and standard tapes are ordered lexicographically like their values.
+    pub fn floor_standard(&self, rule: &Rule) -> Self {
+        self.round_standard(rule).0
+    }
+
+    /// The smallest standard tape with no digits below the lowest index of the tape's range whose
+    /// value is at least that of the tape. See [`Tape::floor_standard`].
+    ///
+    /// If rounding down loses digits, this is the lexicographic successor of the rounded down
+    /// tape, which is not in general the rounded down tape plus `base^low`, as the gaps between
+    /// consecutive standard tapes vary.
+    pub fn ceil_standard(&self, rule: &Rule) -> Self {
+        let (floor, exact) = self.round_standard(rule);
+        if exact {
+            return floor;
+        }
+        let low = self.range().0;
+        (low..)
+            .map(|index| {
+                let mut candidate = floor.clone();
+                for lower in low..index {
+                    candidate[lower] = 0;
+                }
+                candidate[index] += 1;
+                candidate
+            })
+            .find(|candidate| candidate.is_standard(rule))
+            .unwrap()
+    }
+
+    /// The standard form truncated below the lowest index of the range, and whether the
+    /// truncation lost no nonzero digits.
+    fn round_standard(&self, rule: &Rule) -> (Self, bool) {
+        let low = self.range().0;
+        let mut standard = self.clone().normalize_in_place(rule);
+        let mut exact = true;
+        for index in standard.range().0..low {
+            exact &= standard[index] == 0;
+            standard[index] = 0;
+        }
+        (standard, exact)
+    }
+
+    /// [`Tape::standardize`], calling `on_apply` with the current tape and the index the rule is
+    /// about to be applied at before each carry, e.g. to step through the algorithm.
+    ///
+    /// A carry that first applies the rule in reverse further down is reported once, with the
+    /// tape as it was before the reverse application.
+    pub fn standardize_with_callback(
+        &self,
+        rule: &Rule,
+        on_apply: impl FnMut(&Tape, isize),
+    ) -> Self {
+        assert!(self.is_valid(rule));
+        self.clone().normalize_with_callback(rule, on_apply)
+    }
+
+    /// Whether the tapes have the same value, decided exactly by comparing their standard forms.
+    ///
+    /// Unlike `==`, which compares digits, this considers different representations of the same
+    /// value equal.
+    pub fn eq_standard(&self, other: &Tape, rule: &Rule) -> bool {
+        self.standardize(rule) == other.standardize(rule)
+    }
+
+    /// Standardizes the tape using at most `max_applications` rule applications, where a carry
+    /// that first applies the rule in reverse counts as two, see [`Tape::application_cost`].
+    ///
+    /// Returns the resulting tape, which may not be standard yet, and whether it is standard. A
+    /// carry that doesn't fit in the remaining budget is not started.
+    pub fn standardize_bounded(&self, rule: &Rule, max_applications: usize) -> (Self, bool) {
+        assert!(self.is_valid(rule));
+        let mut tape = self.clone();
+        let mut remaining = max_applications;
+        while let Some(index) = tape.highest_violation(rule) {
+            let applications = tape.carry_applications(rule, index);
+            if applications > remaining {
+                return (tape, false);
+            }
+            remaining -= applications;
+            tape = tape.carry_in_place(rule, index);
+        }
+        (tape, true)
+    }
+
+    /// Standardizes the tape by repeatedly applying the rule at the highest index where it can be
+    /// applied directly.
+    ///
+    /// This visits the carries in a different order than [`Tape::standardize`], which always
+    /// resolves the highest violation first, even when that requires applying the rule in reverse
+    /// below it while a direct application is possible further down. When no direct application
+    /// is possible but the tape is not standard yet, e.g. `2 2` under the rule `2 1 1`, the highest
+    /// violation is resolved as in [`Tape::standardize`]. Since standard forms are unique, both
+    /// strategies give the same tape.
+    ///
+    /// Panics unless [`Tape::can_standardize`] holds.
+    pub fn standardize_high_first(&self, rule: &Rule) -> Self {
+        assert!(self.is_valid(rule));
+        // In base one the rule only moves a digit up, so checking inside the loop would never stop.
+        assert!(
+            rule.len() > 1 || rule.first() > 1 || self.is_standard(rule),
+            "Only the zero tape is standard in base one."
+        );
+        let mut tape = self.clone();
+        loop {
+            if let Some(index) = tape.highest_applicable(rule) {
+                tape = tape.apply_in_place(rule, index).unwrap();
+            } else if let Some(index) = tape.highest_violation(rule) {
+                tape = tape.carry_in_place(rule, index);
+            } else {
+                return tape;
+            }
+        }
+    }
+
+    /// The left-first normalization. Written most significant digit first, the highest applicable
+    /// index is the leftmost one, so this is [`Tape::standardize_high_first`].
+    pub fn standardize_left_first(&self, rule: &Rule) -> Self {
+        self.standardize_high_first(rule)
+    }
+
+    /// The highest index at which the rule can be applied without any digit going negative.
+    fn highest_applicable(&self, rule: &Rule) -> Option<isize> {
+        self.index_iter()
+            .map(|index| index + 1)
+            .find(|&index| self.can_apply(rule, index))
+    }
+
+    /// The number of carries [`Tape::standardize`] performs on the tape. Each carry is one or two
+    /// rule applications, see [`Tape::application_cost`].
+    pub fn carry_cost(&self, rule: &Rule) -> usize {
+        let mut carries = 0;
+        self.standardize_with_callback(rule, |_, _| carries += 1);
+        carries
+    }
+
+    /// The number of rule applications [`Tape::standardize`] performs on the tape, counting both
+    /// applications of a carry that first applies the rule in reverse, i.e. the smallest budget
+    /// for which [`Tape::standardize_bounded`] succeeds.
+    pub fn application_cost(&self, rule: &Rule) -> usize {
+        let mut applications = 0;
+        self.standardize_with_callback(rule, |tape, index| {
+            applications += tape.carry_applications(rule, index - 1);
+        });
+        applications
+    }
+
+    /// The standard form of the tape together with the fewest rule applications that reach it,
+    /// found by a shortest path search over the tapes reachable from this one.
+    ///
+    /// The moves are the carries of [`Tape::standardize`]: an application of the rule at some
+    /// index, possibly preceded by a reverse application at most `rule.len()` indices below it.
+    /// Both applications of a carry count, as in [`Tape::application_cost`], so the search weighs
+    /// carries with one and two applications. Reverse applications on their own are not moves,
+    /// since they increase the digit sum and could go on forever. The tapes along the way may be
+    /// invalid. The search is confined to the indices between the tape and its standard form,
+    /// extended by `2 * rule.len()` below and `rule.len()` above, which is as far as the carries of
+    /// [`Tape::standardize`] reach. The result is therefore at most [`Tape::application_cost`]. No
+    /// carry increases the digit sum, so only finitely many tapes fit in this range.
+    ///
+    /// The number of tapes visited can grow exponentially in the width and digit sum of the tape,
+    /// so this is only meant for small tapes. It panics after visiting
+    /// [`OPTIMAL_STANDARDIZE_MAX_STATES`] tapes.
+    pub fn optimal_standardize(&self, rule: &Rule) -> (Tape, usize) {
+        let target = self.standardize(rule);
+        let degree = isize::try_from(rule.len()).unwrap();
+        let (lowest, highest) = match (self.nonzero_range(), target.nonzero_range()) {
+            (Some((low, high)), Some((target_low, target_high))) => {
+                (low.min(target_low), high.max(target_high))
+            }
+            _ => return (target, 0),
+        };
+        let (min, max) = (lowest - 2 * degree, highest + degree);
+
+        // `queue[applications]` holds the tapes reached with that many applications. A tape is
+        // visited the first time it is taken from the queue, which is along a shortest path.
+        let mut queue = vec![vec![self.clone()]];
+        let mut visited = HashSet::new();
+        let mut applications = 0;
+        loop {
+            let tapes = queue
+                .get_mut(applications)
+                .map(std::mem::take)
+                .expect("The standard form is out of reach.");
+            for tape in tapes {
+                if tape == target {
+                    return (target, applications);
+                }
+                if !visited.insert(tape.clone()) {
+                    continue;
+                }
+                for index in (min + degree)..=max {
+                    let reversals = (index - degree..index)
+                        .filter(|&reverse_index| reverse_index - degree >= min)
+                        .map(Some);
+                    for reverse_index in std::iter::once(None).chain(reversals) {
+                        let mut successor = tape.clone();
+                        if let Some(reverse_index) = reverse_index {
+                            if successor[reverse_index] == 0 {
+                                continue;
+                            }
+                            successor = successor.unapply_in_place(rule, reverse_index);
+                        }
+                        let Ok(successor) = successor.apply_in_place(rule, index) else {
+                            continue;
+                        };
+                        if !visited.contains(&successor) {
+                            let distance = applications + 1 + usize::from(reverse_index.is_some());
+                            if queue.len() <= distance {
+                                queue.resize(distance + 1, Vec::new());
+                            }
+                            queue[distance].push(successor);
+                        }
+                    }
+                }
+            }
+            assert!(
+                visited.len() <= OPTIMAL_STANDARDIZE_MAX_STATES,
+                "The tape is too large to search for an optimal standardization."
+            );
+            applications += 1;
+        }
+    }
+
+    /// Adds `amount` to the digit at `index` and renormalizes so the tape is standard afterwards.
+    ///
+    /// The value of the tape increases by `amount * base^index`. Panics if the digit at `index`
+    /// overflows [`Value`], or in base one if the result is nonzero, since only the zero tape is
+    /// standard there.
+    pub fn add_digit_at(&mut self, rule: &Rule, index: isize, amount: Value) {
+        let mut tape = std::mem::replace(self, Tape::zero());
+        tape[index] = tape[index]
+            .checked_add(amount)
+            .expect("The digit overflowed while adding to it.");
+        *self = tape.normalize_in_place(rule);
+    }
+}
+
+/// Chains tape operations under a fixed rule, e.g.
+/// `TapeBuilder::new(&rule).set(1, 1).apply(2).standardize().build()`.
+///
+/// The first failing operation is remembered and all later operations are skipped, so the error
+/// is only handled once in [`TapeBuilder::build`].
+pub struct TapeBuilder<'a> {
+    rule: &'a Rule,
+    tape: Result<Tape, ApplyRuleError>,
+}
+
+impl<'a> TapeBuilder<'a> {
+    /// Starts from the zero tape.
+    pub fn new(rule: &'a Rule) -> Self {
+        Self::from_tape(rule, Tape::zero())
+    }
+
+    pub fn from_tape(rule: &'a Rule, tape: Tape) -> Self {
+        TapeBuilder {
+            rule,
+            tape: Ok(tape),
+        }
+    }
+
+    fn map(self, f: impl FnOnce(Tape, &Rule) -> Result<Tape, ApplyRuleError>) -> Self {
+        let tape = self.tape.and_then(|tape| f(tape, self.rule));
+        TapeBuilder { tape, ..self }
+    }
+
+    /// Sets the digit at `index` to `value`.
+    pub fn set(self, index: isize, value: Value) -> Self {
+        self.map(|mut tape, _| {
+            tape[index] = value;
+            Ok(tape)
+        })
+    }
+
+    /// Applies the rule at `index`, see [`Tape::apply`].
+    pub fn apply(self, index: isize) -> Self {
+        self.map(|tape, rule| tape.apply_in_place(rule, index))
+    }
+
+    /// Standardizes the tape, see [`Tape::standardize`].
+    pub fn standardize(self) -> Self {
+        self.map(|tape, rule| Ok(tape.standardize_in_place(rule)))
+    }
+
+    /// The resulting tape, or the error of the first failed operation.
+    pub fn build(self) -> Result<Tape, ApplyRuleError> {
+        self.tape
+    }
+}
+
+impl Display for Tape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(first_positive) = self.positive_values.first() {
+            for &value in self.positive_values[1..].iter().rev() {
+                write!(f, "{value} ")?;
+            }
+            write!(f, "{first_positive}")?;
+        } else {
+            write!(f, "0")?;
+        }
+        if let Some(last_negative) = self.negative_values.last() {
+            write!(f, ",")?;
+            for &value in self.negative_values[..(self.negative_values.len() - 1)].iter() {
+                write!(f, "{value} ")?;
+            }
+            write!(f, "{last_negative}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Clone for Tape {
+    fn clone(&self) -> Self {
+        Self {
+            positive_values: self.positive_values.clone(),
+            negative_values: self.negative_values.clone(),
+        }
+    }
+
+    /// Copies the digits of `source` into `self`, reusing the capacity of `self`'s storage, so this
+    /// never allocates if `self` already has enough capacity.
+    fn clone_from(&mut self, source: &Self) {
+        self.positive_values.clone_from(&source.positive_values);
+        self.negative_values.clone_from(&source.negative_values);
+    }
+}
+
+impl PartialEq<Tape> for Tape {
+    fn eq(&self, other: &Tape) -> bool {
+        let min_pos_len = self.positive_values.len().min(other.positive_values.len());
+        let min_neg_len = self.negative_values.len().min(other.negative_values.len());
+        self.positive_values[..min_pos_len] == other.positive_values[..min_pos_len]
+            && self.positive_values[min_pos_len..]
+                .iter()
+                .chain(other.positive_values[min_pos_len..].iter())
+                .all(|&x| x == 0)
+            && self.negative_values[..min_neg_len] == other.negative_values[..min_neg_len]
+            && self.negative_values[min_neg_len..]
+                .iter()
+                .chain(other.negative_values[min_neg_len..].iter())
+                .all(|&x| x == 0)
+    }
+}
+
+impl Eq for Tape {}
+
+impl Hash for Tape {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Zeros beyond the highest and lowest nonzero digits are ignored by `==`, so they must not
+        // affect the hash either.
+        fn trimmed(values: &[Value]) -> &[Value] {
+            let len = values.iter().rposition(|&x| x != 0).map_or(0, |i| i + 1);
+            &values[..len]
+        }
+        trimmed(&self.positive_values).hash(state);
+        trimmed(&self.negative_values).hash(state);
+    }
+}
+
+impl Index<isize> for Tape {
+    type Output = Value;
+
+    fn index(&self, index: isize) -> &Self::Output {
+        match Self::internal_index(index) {
+            (true, index) => self.positive_values.get(index).unwrap_or(&0),
+            (false, index) => self.negative_values.get(index).unwrap_or(&0),
+        }
+    }
+}
+
+impl IndexMut<isize> for Tape {
+    fn index_mut(&mut self, index: isize) -> &mut Self::Output {
+        let (positive, index) = Self::internal_index(index);
+        let array = if positive {
+            &mut self.positive_values
+        } else {
+            &mut self.negative_values
+        };
+        if array.len() <= index {
+            array.resize(index + 1, 0);
+        }
+        array.get_mut(index).unwrap()
+    }
+}
+
+impl AddAssign<Tape> for Tape {
+    fn add_assign(&mut self, rhs: Tape) {
+        // Adding zero must not grow the storage of `self` to the range of `rhs`.
+        if rhs.is_zero() {
+            return;
+        }
+        for (self_array, rhs_array) in [
+            (&mut self.positive_values, &rhs.positive_values),
+            (&mut self.negative_values, &rhs.negative_values),
+        ] {
+            if rhs_array.len() > self_array.len() {
+                self_array.resize(rhs_array.len(), 0);
+            }
+            for (value, &rhs_value) in self_array.iter_mut().zip(rhs_array.iter()) {
+                *value += rhs_value
+            }
+        }
+    }
+}
+
+impl Add<Tape> for Tape {
+    type Output = Tape;
+
+    fn add(mut self, rhs: Tape) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn from_arrays() {
+        let x = Tape::from_arrays([1, 2, 3], [4, 5, 6]);
+        assert_eq!(x.positive_values, vec![3, 2, 1]);
+        assert_eq!(x.negative_values, vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn into_arrays() {
+        let x = Tape::from_arrays([1, 2, 3], [4, 5, 6]);
+        let (positives, negatives) = x.clone().into_arrays();
+        assert_eq!(positives, vec![3, 2, 1]);
+        assert_eq!(negatives, vec![4, 5, 6]);
+        let y = Tape::from_vecs(positives, negatives);
+        assert_eq!(x, y);
+        assert_eq!(y.to_string(), "1 2 3,4 5 6");
+    }
+
+    #[test]
+    fn display_trimmed() {
+        let x = Tape::from_arrays([0, 0, 1, 0, 2], [3, 0, 4, 0, 0]);
+        assert_eq!(x.to_string(), "0 0 1 0 2,3 0 4 0 0");
+        assert_eq!(x.display_trimmed(), "1 0 2,3 0 4");
+        let x = Tape::from_arrays([0, 0], [0, 0]);
+        assert_eq!(x.to_string(), "0 0,0 0");
+        assert_eq!(x.display_trimmed(), "0");
+        assert_eq!(Tape::from_arrays([], [0, 1]).display_trimmed(), "0,0 1");
+        assert_eq!(Tape::zero().display_trimmed(), Tape::zero().to_string());
+        let x = Tape::from_arrays([1, 2, 3], [4, 5, 6]);
+        assert_eq!(x.display_trimmed(), x.to_string());
+    }
+
+    #[test]
+    fn from_f64() {
+        let rule = Rule::integer(10);
+        assert_eq!(
+            Tape::from_f64(123.25, &rule, 5),
+            Tape::from_arrays([1, 2, 3], [2, 5])
+        );
+        assert_eq!(Tape::from_f64(0.5, &rule, 5), Tape::from_arrays([], [5]));
+        assert_eq!(Tape::from_f64(0., &rule, 5), Tape::zero());
+        let rule = Rule::from_array([3, 2, 1]).unwrap();
+        let tape = Tape::from_f64(17.3, &rule, 30);
+        assert!(tape.is_standard(&rule));
+        assert_relative_eq!(tape.value(&rule), 17.3, max_relative = 1e-12);
+    }
+
+    #[test]
+    fn from_f64_tol() {
+        let rule = Rule::golden();
+        let value = std::f64::consts::E;
+        let (coarse, coarse_remainder) = Tape::from_f64_tol(value, &rule, 1e-2, 100);
+        let (fine, fine_remainder) = Tape::from_f64_tol(value, &rule, 1e-10, 100);
+        assert!(coarse_remainder < 1e-2);
+        assert!(fine_remainder < 1e-10);
+        assert!(coarse.range().0 > fine.range().0);
+        assert_relative_eq!(coarse.value(&rule) + coarse_remainder, value);
+        assert_relative_eq!(fine.value(&rule) + fine_remainder, value);
+        assert!(coarse
+            .diff(&fine)
+            .iter()
+            .all(|&(index, _, _)| index < coarse.range().0));
+
+        let (capped, capped_remainder) = Tape::from_f64_tol(value, &rule, 1e-10, 3);
+        assert_eq!(capped.range().0, -3);
+        assert!(capped_remainder > 1e-10);
+    }
+
+    #[test]
+    fn refine() {
+        let rule = Rule::golden();
+        let value = std::f64::consts::PI;
+        let mut tape = Tape::from_f64(value, &rule, 5);
+        let error = (tape.value(&rule) - value).abs();
+        tape.refine(value, &rule, 20);
+        assert_eq!(tape.range().0, -25);
+        assert!(tape.is_standard(&rule));
+        assert!((tape.value(&rule) - value).abs() < error);
+        assert_eq!(tape, Tape::from_f64(value, &rule, 25));
+        let mut integer = Tape::from_f64(2., &Rule::integer(10), 0);
+        integer.refine(2.25, &Rule::integer(10), 3);
+        assert_eq!(integer, Tape::from_arrays([2], [2, 5, 0]));
+    }
+
+    #[test]
+    fn from_cf() {
+        let rule = Rule::golden();
+        let tape = Tape::from_cf(2., &rule, 10).unwrap();
+        assert_eq!(tape, Tape::from_arrays([1, 0], [0, 1]));
+        let tape = Tape::from_cf(1. / rule.base(), &rule, 10).unwrap();
+        assert_eq!(tape, Tape::from_arrays([], [1]));
+        let tape = Tape::from_cf(std::f64::consts::PI, &rule, 40).unwrap();
+        assert!(tape.is_standard(&rule));
+        assert_relative_eq!(tape.value(&rule), std::f64::consts::PI, max_relative = 1e-8);
+        assert!(Tape::from_cf(2., &Rule::tribonacci(), 10).is_err());
+        assert!(Tape::from_cf(2., &Rule::integer(10), 10).is_err());
+    }
+
+    #[test]
+    fn clone_from() {
+        let x = Tape::from_arrays([1, 2, 3], [4, 5, 6]);
+        let mut dst = Tape::from_arrays([0; 8], [0; 8]);
+        let positive_ptr = dst.positive_values.as_ptr();
+        let negative_ptr = dst.negative_values.as_ptr();
+        dst.clone_from(&x);
+        assert_eq!(dst, x);
+        assert_eq!(dst.range(), x.range());
+        assert_eq!(dst.positive_values.as_ptr(), positive_ptr);
+        assert_eq!(dst.negative_values.as_ptr(), negative_ptr);
+    }
+
+    #[test]
+    fn digit() {
+        let x = Tape::from_arrays([1, 0, 3], [4, 5]);
+        assert_eq!(x.digit(0), 3);
+        assert_eq!(x.digit(1), 0);
+        assert_eq!(x.digit(2), 1);
+        assert_eq!(x.digit(3), 0);
+        assert_eq!(x.frac_digit(0), 4);
+        assert_eq!(x.frac_digit(1), 5);
+        assert_eq!(x.frac_digit(2), 0);
+        for pos in 0..5 {
+            assert_eq!(x.digit(pos), x[isize::try_from(pos).unwrap()]);
+            assert_eq!(x.frac_digit(pos), x[-isize::try_from(pos).unwrap() - 1]);
+        }
+        assert_eq!(Tape::zero().digit(0), 0);
+        assert_eq!(Tape::zero().frac_digit(0), 0);
+    }
+
+    #[test]
+    fn set_range() {
+        let mut tape = Tape::from_arrays([1, 1], [1]);
+        tape.set_range(2, &[3, 0, 4, 5, 6]);
+        assert_eq!(tape, Tape::from_arrays([3, 0, 4], [5, 6]));
+        assert_eq!(tape.digits_between(2, -2), vec![3, 0, 4, 5, 6]);
+        assert_eq!(tape.digits_between(4, 1), vec![0, 0, 3, 0]);
+        assert_eq!(tape.digits_between(-3, -4), vec![0, 0]);
+        assert!(tape.digits_between(0, 1).is_empty());
+        tape.set_range(-5, &[7]);
+        assert_eq!(tape.range(), (-5, 3));
+        assert_eq!(tape.digits_between(-4, -5), vec![0, 7]);
+        tape.set_range(0, &[]);
+        assert_eq!(tape.digits_between(0, 0), vec![4]);
+    }
+
+    #[test]
+    fn get() {
+        let x = Tape::from_arrays([1, 0, 3], [0, 5]);
+        assert_eq!(x.get(2), Some(1));
+        assert_eq!(x.get(1), Some(0));
+        assert_eq!(x.get(-1), Some(0));
+        assert_eq!(x.get(-2), Some(5));
+        assert_eq!(x.get(3), None);
+        assert_eq!(x.get(-3), None);
+        assert_eq!(x[3], 0);
+        assert_eq!(Tape::zero().get(0), None);
+    }
+
+    #[test]
+    fn rle() {
+        let tape = Tape::from_arrays([1, 1, 1, 0, 0, 0, 0, 2], [2, 2, 0, 0, 0, 0, 0, 1]);
+        let runs = tape.to_rle();
+        assert_eq!(runs, vec![(1, 3), (0, 4), (2, 3), (0, 5), (1, 1)]);
+        let negative_len = usize::try_from(-tape.range().0).unwrap();
+        let decoded = Tape::from_rle(&runs, negative_len);
+        assert_eq!(decoded.positive_values, tape.positive_values);
+        assert_eq!(decoded.negative_values, tape.negative_values);
+        assert!(Tape::zero().to_rle().is_empty());
+        assert_eq!(Tape::from_rle(&[], 0).range(), (0, 0));
+    }
+
+    #[test]
+    fn eq() {
+        let x = Tape::from_arrays([0, 1, 2, 3], [4, 5, 6]);
+        let y = Tape::from_arrays([1, 2, 3], [4, 5, 6, 0]);
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    fn hash() {
+        let hash = |tape: &Tape| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            tape.hash(&mut hasher);
+            hasher.finish()
+        };
+        let x = Tape::from_arrays([0, 1, 2, 3], [4, 5, 6]);
+        let y = Tape::from_arrays([1, 2, 3], [4, 5, 6, 0]);
+        assert_eq!(hash(&x), hash(&y));
+        assert_eq!(hash(&Tape::zero()), hash(&Tape::from_arrays([0, 0], [0])));
+        // Moving digits across the radix point must change the hash.
+        assert_ne!(
+            hash(&Tape::from_arrays([1], [])),
+            hash(&Tape::from_arrays([], [1]))
+        );
+    }
+
+    #[test]
+    fn apply_near_index_limits() {
+        let rule = Rule::golden();
+        for index in [isize::MIN, isize::MIN + 1] {
+            let error = Tape::zero().apply(&rule, index).unwrap_err();
+            assert!(matches!(error, ApplyRuleError::IndexOverflow(_)), "{index}");
+            assert!(!Tape::zero().can_apply(&rule, index));
+        }
+        // Within range, the missing digits are simply too small and nothing is allocated.
+        for index in [isize::MIN + 2, isize::MAX - 1, isize::MAX] {
+            let error = Tape::zero().apply(&rule, index).unwrap_err();
+            assert!(
+                matches!(
+                    error,
+                    ApplyRuleError::InsufficientDigit { tape_value: 0, .. }
+                ),
+                "{index}"
+            );
+            assert!(!Tape::zero().can_apply(&rule, index));
+        }
+        let tape = Tape::from_arrays([1, 1], []);
+        assert_eq!(
+            tape.clone().apply_in_place(&rule, 2).unwrap().range(),
+            (0, 3)
+        );
+        assert_eq!(tape.predict_apply_range(&rule, isize::MIN), (isize::MIN, 2));
+        assert!(tape.is_standard(&Rule::integer(2)));
+        assert_eq!(tape.standardize(&rule), Tape::from_arrays([1, 0, 0], []));
+    }
+
+    #[test]
+    fn to_latex() {
+        let rule = Rule::golden();
+        let tape = Tape::from_arrays([1, 0, 1], [1]);
+        let latex = tape.to_latex(&rule);
+        assert!(latex.starts_with(r"\beta^{2} + \beta^{0} + \beta^{-1} \approx 4.236"));
+        let value: f64 = latex.split(r"\approx ").nth(1).unwrap().parse().unwrap();
+        assert_relative_eq!(value, tape.value(&rule), max_relative = 1e-12);
+        let latex = Tape::from_arrays([3, 0], [0, 2]).to_latex(&Rule::integer(10));
+        assert_eq!(latex, r"3 \beta^{1} + 2 \beta^{-2} \approx 30.02");
+        assert_eq!(Tape::zero().to_latex(&rule), r"0 \approx 0");
+    }
+
+    #[test]
+    fn applicable_indices() {
+        let rule = Rule::from_array([2, 1]).unwrap();
+        let tape = Tape::from_arrays([2, 1, 2, 3], [1, 0]);
+        let indices = tape.applicable_indices(&rule);
+        assert_eq!(indices, vec![4, 2, 1]);
+        for index in tape.index_iter().map(|index| index + 1).chain([-5, 6]) {
+            assert_eq!(
+                indices.contains(&index),
+                tape.apply(&rule, index).is_ok(),
+                "{index}"
+            );
+        }
+        assert!(Tape::zero().applicable_indices(&rule).is_empty());
+        assert!(Tape::from_arrays([1, 1], [1])
+            .applicable_indices(&rule)
+            .is_empty());
+    }
+
+    #[test]
+    fn digits_order() {
+        let tape = Tape::from_arrays([1, 2], [3, 4]);
+        assert_eq!(tape.digits_be(), vec![(1, 1), (0, 2), (-1, 3), (-2, 4)]);
+        assert_eq!(tape.digits_le(), vec![(-2, 4), (-1, 3), (0, 2), (1, 1)]);
+        for tape in [
+            tape,
+            Tape::from_arrays([5, 0, 7], []),
+            Tape::from_arrays([], [0, 6]),
+            Tape::zero(),
+        ] {
+            let mut reversed = tape.digits_be();
+            reversed.reverse();
+            assert_eq!(reversed, tape.digits_le());
+        }
+    }
+
+    #[test]
+    fn can_standardize() {
+        let rules = [
+            Rule::integer(1),
+            Rule::integer(2),
+            Rule::golden(),
+            Rule::from_array([2, 1, 1]).unwrap(),
+        ];
+        for rule in rules {
+            for digits in (0..4).map(|_| 0..=3).multi_cartesian_product() {
+                let tape = Tape::from_arrays(&digits[..2], &digits[2..]);
+                let panicked = std::panic::catch_unwind(|| tape.standardize(&rule)).is_err();
+                assert_eq!(
+                    tape.can_standardize(&rule),
+                    !panicked,
+                    "{tape} under {rule:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn optimal_standardize() {
+        let rule = Rule::from_array([2, 1, 1]).unwrap();
+        let tape = Tape::from_arrays([2, 2, 0], [2, 2]);
+        assert_eq!(tape.carry_cost(&rule), 4);
+        assert_eq!(tape.application_cost(&rule), 7);
+        assert_eq!(
+            tape.optimal_standardize(&rule),
+            (tape.standardize(&rule), 3)
+        );
+        let rule = Rule::golden();
+        assert_eq!(Tape::zero().optimal_standardize(&rule), (Tape::zero(), 0));
+        let standard = Tape::from_arrays([1, 0, 1], []);
+        assert_eq!(standard.optimal_standardize(&rule), (standard, 0));
+
+        let rules = [
+            Rule::golden(),
+            Rule::integer(2),
+            Rule::from_array([2, 1]).unwrap(),
+            Rule::from_array([2, 1, 1]).unwrap(),
+        ];
+        for rule in rules {
+            let digits = (0..5).map(|_| 0..=rule.first()).multi_cartesian_product();
+            for digits in digits {
+                let tape = Tape::from_arrays(&digits[..3], &digits[3..]);
+                let (standard, applications) = tape.optimal_standardize(&rule);
+                assert_eq!(standard, tape.standardize(&rule));
+                assert!(
+                    applications <= tape.application_cost(&rule),
+                    "{tape} under {rule:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn interleave() {
+        let a = Tape::from_arrays([1, 2], [3]);
+        let b = Tape::from_arrays([4], [5, 6]);
+        let interleaved = Tape::interleave(&a, &b);
+        assert_eq!(interleaved, Tape::from_arrays([1, 4, 2], [5, 3, 6]));
+        assert_eq!(interleaved.deinterleave(), (a.clone(), b.clone()));
+        let rule = Rule::integer(4);
+        assert_relative_eq!(
+            interleaved.value(&Rule::integer(2)),
+            a.value(&rule) + 2. * b.value(&rule),
+            max_relative = 1e-12
+        );
+        assert_eq!(
+            Tape::interleave(&Tape::zero(), &Tape::zero()).deinterleave(),
+            (Tape::zero(), Tape::zero())
+        );
+    }
+
+    #[test]
+    fn shift() {
+        let tape = Tape::from_arrays([1, 2], [3]);
+        assert_eq!(tape.shift(0), tape);
+        assert_eq!(tape.shift(2), Tape::from_arrays([1, 2, 3, 0], []));
+        assert_eq!(tape.shift(-1), Tape::from_arrays([1], [2, 3]));
+        assert_eq!(tape.shift(-3), Tape::from_arrays([], [0, 1, 2, 3]));
+        assert_eq!(tape.shift(-3).shift(3), tape);
+        assert_eq!(Tape::zero().shift(5), Tape::zero());
+        let rule = Rule::golden();
+        assert_relative_eq!(
+            tape.shift(3).value(&rule),
+            tape.value(&rule) * rule.base().powi(3),
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn shift_standard() {
+        let rule = Rule::from_array([2, 1, 1]).unwrap();
+        let standard = Tape::from_arrays([2, 0, 1, 2], [1]);
+        assert!(standard.is_standard(&rule));
+        for k in -6..=6 {
+            // The shortcut returns the shifted storage untouched.
+            let shifted = standard.shift_standard(k, &rule);
+            assert_eq!(shifted, standard.shift(k).standardize(&rule));
+            assert_eq!(shifted.range(), standard.shift(k).range());
+        }
+        let non_standard = Tape::from_arrays([2, 2, 2], [2]);
+        assert!(!non_standard.is_standard(&rule));
+        for k in -6..=6 {
+            let shifted = non_standard.shift_standard(k, &rule);
+            assert_eq!(shifted, non_standard.shift(k).standardize(&rule));
+            assert!(shifted.is_standard(&rule));
+        }
+    }
+
+    #[test]
+    fn standardize_cached() {
+        let rule = Rule::golden();
+        let mut cache = StandardizeCache::new();
+        let tape = Tape::from_arrays([1, 1, 1, 1], []);
+        let first = tape.standardize_cached(&rule, &mut cache);
+        assert_eq!(first, tape.standardize(&rule));
+        assert_eq!(cache.len(), 1);
+        // An equal tape with extra zeros hits the same entry.
+        let padded = Tape::from_arrays([0, 1, 1, 1, 1], [0]);
+        assert_eq!(padded.standardize_cached(&rule, &mut cache), first);
+        assert_eq!(cache.len(), 1);
+
+        // Poison the entry to show that the second call is answered from the cache.
+        let marker = Tape::from_arrays([1], []);
+        cache.insert(tape.clone(), marker.clone());
+        assert_eq!(tape.standardize_cached(&rule, &mut cache), marker);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn add() {
+        let x = Tape::from_arrays([1, 2], [3, 4, 5, 6]);
+        let y = Tape::from_arrays([1, 2, 3, 4], [5]);
+        let z = x + y;
+        assert_eq!(z.positive_values, vec![6, 4, 2, 1]);
+        assert_eq!(z.negative_values, vec![8, 4, 5, 6]);
+        assert_eq!(z, Tape::from_arrays([1, 2, 4, 6], [8, 4, 5, 6]));
+    }
+
+    #[test]
+    fn longest_run() {
+        let tape = Tape::from_arrays([1, 1, 0, 0, 0, 2], []);
+        assert_eq!(tape.longest_nonzero_run(), 2);
+        assert_eq!(tape.longest_zero_run(), 3);
+        let tape = Tape::from_arrays([1, 0], [3, 4, 5]);
+        assert_eq!(tape.longest_nonzero_run(), 3);
+        assert_eq!(tape.longest_zero_run(), 1);
+        assert_eq!(Tape::zero().longest_nonzero_run(), 0);
+        assert_eq!(Tape::zero().longest_zero_run(), 0);
+    }
+
+    #[test]
+    fn fold_digits() {
+        let tape = Tape::from_arrays([1, 2, 3], [4, 0, 6]);
+        assert_eq!(tape.digit_sum(), 16);
+        assert_eq!(
+            tape.fold_digits(0, |acc, _, value| acc + u64::from(value)),
+            tape.digit_sum()
+        );
+        let digits = tape.fold_digits(vec![], |mut acc, index, value| {
+            acc.push((index, value));
+            acc
+        });
+        assert_eq!(
+            digits,
+            vec![(2, 1), (1, 2), (0, 3), (-1, 4), (-2, 0), (-3, 6)]
+        );
+        assert_eq!(Tape::zero().digit_sum(), 0);
+    }
+
+    #[test]
+    fn map_digits() {
+        let tape = Tape::from_arrays([1, 2, 3], [4, 5]);
+        let doubled = tape.map_digits(
+            |index, value| {
+                if index % 2 == 0 {
+                    value * 2
+                } else {
+                    value
+                }
+            },
+        );
+        assert_eq!(doubled, Tape::from_arrays([2, 2, 6], [4, 10]));
+        assert_eq!(doubled.range(), tape.range());
+        assert_eq!(tape.map_digits(|_, value| value), tape);
+    }
+
+    #[test]
+    fn digit_histogram() {
+        let tape = Tape::from_arrays([1, 1, 0, 0, 0, 2], [3]);
+        assert_eq!(tape.digit_histogram(), vec![3, 2, 1, 1]);
+        assert_eq!(Tape::zero().digit_histogram(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn digit_entropy() {
+        assert_relative_eq!(Tape::zero().digit_entropy(), 0.);
+        assert_relative_eq!(Tape::from_arrays([0, 0], [0]).digit_entropy(), 0.);
+        assert_relative_eq!(Tape::from_arrays([3], []).digit_entropy(), 0.);
+        let tape = Tape::from_arrays([0, 1, 2, 3], [3, 2, 1, 0]);
+        assert_relative_eq!(tape.digit_entropy(), 2_f64.log2() * 2.);
+        let tape = Tape::from_arrays([0, 1, 2], [0, 1, 2]);
+        assert_relative_eq!(tape.digit_entropy(), 3_f64.log2(), max_relative = 1e-12);
+        let tape = Tape::from_arrays([1, 0, 0, 0], []);
+        assert!(tape.digit_entropy() < 1.);
+    }
+
+    #[test]
+    fn common_prefix_len() {
+        let x = Tape::from_arrays([1, 0, 1], [1, 0, 0, 1]);
+        let y = Tape::from_arrays([1, 0, 1], [1, 0, 1]);
+        assert_eq!(x.common_prefix_len(&y), 5);
+        assert_eq!(y.common_prefix_len(&x), 5);
+        let z = Tape::from_arrays([0, 0, 1, 0, 1], [1]);
+        assert_eq!(x.common_prefix_len(&z), 8);
+        assert_eq!(x.common_prefix_len(&x), 7);
+        assert_eq!(x.common_prefix_len(&Tape::zero()), 0);
+        assert_eq!(Tape::zero().common_prefix_len(&Tape::zero()), 0);
+    }
+
+    #[test]
+    fn value_from_digits() {
+        let rule = Rule::from_array([2, 1]).unwrap();
+        let tape = Tape::from_arrays([2, 0, 1, 1], [2, 0, 1]);
+        let digits = tape.to_sparse();
+        assert_eq!(
+            super::value_from_digits(digits.iter().copied(), &rule),
+            tape.value(&rule)
+        );
+        assert_relative_eq!(
+            super::value_from_digits(digits.iter().rev().copied(), &rule),
+            tape.value(&rule),
+            max_relative = 1e-12
+        );
+        let rule = Rule::integer(10);
+        let digits = (0..5).map(|index| (index, 1)).chain([(2, 3), (-1, 5)]);
+        assert_relative_eq!(super::value_from_digits(digits, &rule), 11411.5);
+    }
+
+    #[test]
+    fn value_kahan() {
+        // Each of the low digits is lost to rounding in the naive sum.
+        let rule = Rule::integer(2);
+        let mut tape = Tape::zero();
+        tape[60] = 1;
+        for index in 0..8 {
+            tape[index] = 1;
+        }
+        let reference = ((1u64 << 60) + 255) as f64;
+        let naive_error = (tape.value(&rule) - reference).abs();
+        let kahan_error = (tape.value_kahan(&rule) - reference).abs();
+        assert!(kahan_error < naive_error);
+        assert_eq!(tape.value_kahan(&rule), reference);
+
+        let rule = Rule::golden();
+        let tape = Tape::from_arrays([1, 0, 1, 0, 0, 1], [0, 1, 0, 1]);
+        assert_relative_eq!(
+            tape.value_kahan(&rule),
+            tape.value(&rule),
+            max_relative = 1e-15
+        );
+        assert_eq!(Tape::zero().value_kahan(&rule), 0.);
+    }
+
+    /// The exact value `a + b * phi` of a golden ratio tape as integers `(a, b)`.
+    fn golden_value_coefficients(tape: &Tape) -> (i64, i64) {
+        let power = |index: isize| {
+            let (mut a, mut b) = (1_i64, 0_i64);
+            for _ in 0..index.unsigned_abs() {
+                (a, b) = if index > 0 { (b, a + b) } else { (b - a, a) };
+            }
+            (a, b)
+        };
+        tape.to_sparse()
+            .into_iter()
+            .fold((0, 0), |(a, b), (index, value)| {
+                let (power_a, power_b) = power(index);
+                (
+                    a + i64::from(value) * power_a,
+                    b + i64::from(value) * power_b,
+                )
+            })
+    }
+
+    #[test]
+    fn value_with_error() {
+        // Error-free transformations giving the sum and product of two floats exactly as `hi + lo`.
+        let two_sum = |a: f64, b: f64| {
+            let sum = a + b;
+            let b_virtual = sum - a;
+            (sum, (a - (sum - b_virtual)) + (b - b_virtual))
+        };
+        let two_product = |a: f64, b: f64| {
+            let product = a * b;
+            (product, a.mul_add(b, -product))
+        };
+        // phi as an unevaluated sum `hi + lo` with about 106 bits of precision.
+        let sqrt_5 = 5f64.sqrt();
+        let sqrt_5_lo = (-sqrt_5).mul_add(sqrt_5, 5.) / (2. * sqrt_5);
+        let (one_plus_hi, one_plus_lo) = two_sum(1., sqrt_5);
+        let (phi_hi, phi_lo) = (one_plus_hi / 2., (one_plus_lo + sqrt_5_lo) / 2.);
+
+        let rule = Rule::golden();
+        let tapes = [
+            Tape::from_arrays([1, 0, 1, 0, 0, 1], [0, 1, 0, 1]),
+            Tape::from_arrays([1; 30], [1; 30]),
+            Tape::from_arrays([1], []).shift(40),
+            Tape::from_arrays([1, 1, 0, 1], []).shift(-30),
+            Tape::from_arrays([1], []),
+        ];
+        for tape in tapes {
+            let (value, error) = tape.value_with_error(&rule);
+            assert_eq!(value, tape.value(&rule));
+            let (a, b) = golden_value_coefficients(&tape);
+            let (product_hi, product_lo) = two_product(b as f64, phi_hi);
+            let (exact_hi, sum_lo) = two_sum(a as f64, product_hi);
+            let exact_lo = sum_lo + product_lo + b as f64 * phi_lo;
+            let actual_error = ((value - exact_hi) - exact_lo).abs();
+            assert!(actual_error <= error, "{tape}: {actual_error} > {error}");
+            assert!(error <= 1e-13 * value, "{tape}: {error}");
+        }
+
+        let rule = Rule::integer(2);
+        let mut tape = Tape::zero();
+        tape[60] = 1;
+        for index in 0..8 {
+            tape[index] = 1;
+        }
+        let (value, error) = tape.value_with_error(&rule);
+        let exact = (1u64 << 60) + 255;
+        let actual_error = (value as u64).abs_diff(exact) as f64;
+        assert!(actual_error > 0.);
+        assert!(actual_error <= error);
+        assert_eq!(Tape::zero().value_with_error(&rule), (0., 0.));
+    }
+
+    #[test]
+    fn integer_and_fractional_value() {
+        let rule = Rule::integer(10);
+        let tape = Tape::from_arrays([1, 2, 3], [4, 5]);
+        assert_relative_eq!(tape.integer_value(&rule), 123.);
+        assert_relative_eq!(tape.fractional_value(&rule), 0.45);
+        let rule = Rule::golden();
+        let tape = Tape::from_arrays([1, 0, 1], [1, 0, 1, 0, 1]);
+        let (integer, fractional) = (tape.integer_value(&rule), tape.fractional_value(&rule));
+        assert_relative_eq!(
+            integer + fractional,
+            tape.value(&rule),
+            max_relative = 1e-12
+        );
+        assert!(integer >= 1.);
+        assert!((0. ..1.).contains(&fractional));
+        assert_eq!(Tape::from_arrays([], [1]).integer_value(&rule), 0.);
+        assert_eq!(Tape::from_arrays([2], []).fractional_value(&rule), 0.);
+    }
+
+    #[test]
+    fn integer_sort_key() {
+        let rule = Rule::integer(10);
+        assert_eq!(
+            Tape::from_arrays([2, 5, 6], []).integer_sort_key(&rule),
+            Some(vec![0, 0, 0, 0, 0, 0, 0, 2, 1, 0])
+        );
+        assert_eq!(Tape::zero().integer_sort_key(&rule), Some(vec![0; 8]));
+        let mut tapes = [
+            Tape::from_arrays([1, 0, 0, 0, 0, 0], []),
+            Tape::from_arrays([9, 9], []),
+            Tape::from_arrays([0, 0, 0, 7], [0]),
+            Tape::from_arrays([2, 5, 6], []),
+            Tape::from_arrays([2, 5, 5], []),
+            Tape::from_arrays([1, 15], []),
+            Tape::zero(),
+            Tape::from_arrays([4, 2, 9, 4, 9, 6, 7, 2, 9, 6], []),
+        ];
+        tapes.sort_by_key(|tape| tape.integer_sort_key(&rule).unwrap());
+        let values = tapes.iter().map(|tape| tape.value(&rule)).collect_vec();
+        assert!(values.iter().tuple_windows().all(|(a, b)| a < b));
+        // 25 written with a non-standard digit gets the same key.
+        assert_eq!(
+            Tape::from_arrays([1, 15], []).integer_sort_key(&rule),
+            Tape::from_arrays([2, 5], []).integer_sort_key(&rule)
+        );
+
+        assert_eq!(Tape::from_arrays([1], [1]).integer_sort_key(&rule), None);
+        assert_eq!(
+            Tape::from_arrays([1], []).integer_sort_key(&Rule::golden()),
+            None
+        );
+    }
+
+    #[test]
+    fn value_contributions() {
+        let rule = Rule::from_array([2]).unwrap();
+        let tape = Tape::from_arrays([1, 0, 2], [0, 1]);
+        assert_eq!(
+            tape.value_contributions(&rule),
+            vec![(2, 4.), (0, 2.), (-2, 0.25)]
+        );
+        let rule = Rule::from_array([2, 1]).unwrap();
+        let tape = Tape::from_arrays([2, 0, 1, 1], [2, 0, 1]);
+        let contributions = tape.value_contributions(&rule);
+        assert_eq!(contributions.len(), 5);
+        assert_relative_eq!(
+            contributions
+                .iter()
+                .map(|&(_, contribution)| contribution)
+                .sum::<f64>(),
+            tape.value(&rule),
+            max_relative = 1e-12
+        );
+        assert!(Tape::zero().value_contributions(&rule).is_empty());
+    }
+
+    #[test]
+    fn value_mod() {
+        let rule = Rule::from_array([10]).unwrap();
+        let tape = Tape::from_arrays([9, 8, 7, 6, 5, 4, 3, 2, 1], [0, 0]);
+        assert_eq!(tape.value_mod(&rule, 7), Some(987654321 % 7));
+        assert_eq!(tape.value_mod(&rule, 1), Some(0));
+        assert_eq!(tape.value_mod(&rule, 0), None);
+        assert_eq!(tape.value_mod(&rule, u64::MAX), Some(987654321));
+        let tape = Tape::from_arrays([1; 40], []);
+        assert_eq!(tape.value_mod(&rule, 9), Some(40 % 9));
+        assert_eq!(Tape::from_arrays([1], [1]).value_mod(&rule, 7), None);
+        let rule = Rule::from_array([1, 1]).unwrap();
+        assert_eq!(Tape::from_arrays([1], []).value_mod(&rule, 7), None);
+    }
+
+    #[test]
+    fn regroup() {
+        let binary = Rule::from_array([2]).unwrap();
+        let quaternary = Rule::from_array([4]).unwrap();
+        let tape = Tape::from_arrays([1, 1, 0, 1, 1], [1, 0, 1]);
+        let grouped = tape.regroup(2, &binary).unwrap();
+        assert_eq!(grouped, Tape::from_arrays([1, 2, 3], [2, 2]));
+        assert_relative_eq!(grouped.value(&quaternary), tape.value(&binary));
+        assert_eq!(grouped.ungroup(2, &binary), tape);
+
+        let decimal = Rule::from_array([10]).unwrap();
+        let tape = Tape::from_arrays([1, 2, 3, 4, 5], [6, 7]);
+        let grouped = tape.regroup(3, &decimal).unwrap();
+        assert_eq!(grouped, Tape::from_arrays([12, 345], [670]));
+        assert_eq!(grouped.ungroup(3, &decimal), tape);
+
+        assert_eq!(
+            Tape::from_arrays([9; 9], []).regroup(9, &decimal),
+            Some(Tape::from_arrays([999_999_999], []))
+        );
+        assert_eq!(Tape::from_arrays([9; 10], []).regroup(10, &decimal), None);
+        assert_eq!(Tape::from_arrays([1; 12], []).regroup(11, &decimal), None);
+        // Zeros don't need their weight to fit.
+        assert_eq!(
+            Tape::from_arrays([0; 12], []).regroup(11, &decimal),
+            Some(Tape::zero())
+        );
+    }
+
+    #[test]
+    fn sparse() {
+        let x = Tape::from_arrays([1, 0, 0, 0, 2], [0, 0, 3]);
+        assert_eq!(x.to_sparse(), vec![(4, 1), (0, 2), (-3, 3)]);
+        assert_eq!(Tape::from_sparse(&x.to_sparse()), x);
+        let y = Tape::from_sparse(&[(100, 1), (0, 4), (-50, 2)]);
+        let sum = Tape::add_sparse(&x.to_sparse(), &y.to_sparse());
+        assert_eq!(sum, vec![(100, 1), (4, 1), (0, 6), (-3, 3), (-50, 2)]);
+        assert_eq!(Tape::from_sparse(&sum), x.clone() + y.clone());
+        let rule = Rule::from_array([1, 1]).unwrap();
+        assert_relative_eq!(
+            Tape::from_sparse(&sum).value(&rule),
+            x.value(&rule) + y.value(&rule),
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn from_balanced() {
+        let rule = Rule::golden();
+        let phi = rule.base();
+        // phi^2 - 1 = phi.
+        let tape = Tape::from_balanced(&[1, 0, -1], &[], &rule);
+        assert_eq!(tape, Tape::from_arrays([1, 0], []));
+        // phi - phi^-1 = 1.
+        let tape = Tape::from_balanced(&[1, 0], &[-1], &rule);
+        assert_eq!(tape, Tape::from_arrays([1], []));
+        let tape = Tape::from_balanced(&[1, -1, 0, 1, -1], &[1, -1], &rule);
+        assert!(tape.is_standard(&rule));
+        assert_relative_eq!(
+            tape.value(&rule),
+            phi.powi(4) - phi.powi(3) + phi - 1. + phi.powi(-1) - phi.powi(-2),
+            max_relative = 1e-12
+        );
+        assert_eq!(
+            Tape::from_balanced(&[1, -1], &[], &Rule::integer(2)),
+            Tape::from_arrays([1], [])
+        );
+        assert_eq!(
+            Tape::from_balanced(&[1, -1, 1], &[-1], &rule),
+            Tape::from_balanced(&[1, -1, 1], &[-1], &rule).standardize(&rule)
+        );
+        assert_eq!(Tape::from_balanced(&[], &[], &rule), Tape::zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the zero tape is standard in base one.")]
+    fn standardize_high_first_base_one() {
+        let rule = Rule::integer(1);
+        assert_eq!(Tape::zero().standardize_high_first(&rule), Tape::zero());
+        Tape::from_arrays([1], []).standardize_high_first(&rule);
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_balanced_negative() {
+        Tape::from_balanced(&[-1, 1], &[], &Rule::golden());
+    }
+
+    #[test]
+    fn signed_conversions() {
+        let tape = Tape::from_arrays([Value::MAX, 0, 1], [0, 2]);
+        assert_eq!(
+            tape.signed_digit_sum(),
+            SignedValue::from(Value::MAX) + 1 + 2
+        );
+        let map = tape.to_signed_map();
+        assert_eq!(
+            map,
+            BTreeMap::from([(2, SignedValue::from(Value::MAX)), (0, 1), (-2, 2)])
+        );
+        assert_eq!(Tape::try_from_signed_map(&map), Some(tape));
+
+        let mut map = BTreeMap::from([(0, 0), (-1, 1)]);
+        assert_eq!(
+            Tape::try_from_signed_map(&map),
+            Some(Tape::from_arrays([0], [1]))
+        );
+        map.insert(1, -1);
+        assert_eq!(Tape::try_from_signed_map(&map), None);
+        map.insert(1, SignedValue::from(Value::MAX) + 1);
+        assert_eq!(Tape::try_from_signed_map(&map), None);
+        assert_eq!(Tape::zero().signed_digit_sum(), 0);
+    }
+
+    #[test]
+    fn add_zero() {
+        assert!(Tape::zero().is_zero());
+        assert!(Tape::from_arrays([0, 0], [0]).is_zero());
+        assert!(!Tape::from_arrays([0, 1], [0]).is_zero());
+        assert!(!Tape::from_arrays([], [0, 1]).is_zero());
+
+        let tape = Tape::from_arrays([1, 2], [3]);
+        let mut sum = tape.clone();
+        let capacity = sum.positive_values.capacity();
+        sum += Tape::from_arrays([0; 100], [0; 100]);
+        assert_eq!(sum.range(), tape.range());
+        assert_eq!(sum.positive_values.capacity(), capacity);
+        assert_eq!(sum, tape);
+        sum += Tape::zero();
+        assert_eq!(sum, tape);
+        let mut zero = Tape::zero();
+        zero += tape.clone();
+        assert_eq!(zero, tape);
+    }
+
+    #[test]
+    fn pack() {
+        let rule = Rule::golden();
+        let tape = Tape::from_arrays([1, 0, 1, 0, 0, 1, 0, 1, 0], [0, 1, 0, 0, 1]);
+        let packed = tape.pack(&rule);
+        assert_eq!(packed.len(), 2);
+        assert_eq!(packed, vec![0b1010_0101, 0b0010_0100]);
+        let unpacked = Tape::unpack(&packed, &rule, tape.range()).unwrap();
+        assert_eq!(unpacked.range(), tape.range());
+        assert_eq!(unpacked, tape);
+
+        let rule = Rule::integer(10);
+        let tape = Tape::from_arrays([9, 0, 3, 7], [1, 8, 5]);
+        let packed = tape.pack(&rule);
+        assert_eq!(packed.len(), 4);
+        assert_eq!(Tape::unpack(&packed, &rule, tape.range()), Some(tape));
+        assert!(Tape::zero().pack(&rule).is_empty());
+        assert_eq!(Tape::unpack(&[], &rule, (0, 0)), Some(Tape::zero()));
+
+        // Malformed input is rejected rather than read out of bounds.
+        assert_eq!(Tape::unpack(&[1], &Rule::golden(), (0, 20)), None);
+        assert_eq!(Tape::unpack(&packed[..3], &rule, (-3, 4)), None);
+        assert_eq!(Tape::unpack(&[0; 4], &rule, (1, 4)), None);
+        assert_eq!(Tape::unpack(&[0; 4], &rule, (-4, -1)), None);
+        assert_eq!(Tape::unpack(&[], &rule, (isize::MIN, isize::MAX)), None);
+        // 15 doesn't fit the decimal rule even though it fits in four bits.
+        assert_eq!(Tape::unpack(&[0x0f], &rule, (0, 1)), None);
+    }
+
+    #[test]
+    fn index_map() {
+        let x = Tape::from_arrays([1, 0, 0, 0, 2], [0, 0, 3]);
+        let map = x.to_index_map();
+        assert_eq!(map, BTreeMap::from([(4, 1), (0, 2), (-3, 3)]));
+        let mut round_trip = Tape::zero();
+        round_trip.add_index_map(&map);
+        assert_eq!(round_trip, x);
+
+        let y = Tape::from_sparse(&[(100, 1), (0, 4), (-50, 2)]);
+        let mut sum = x.clone();
+        sum.add_index_map(&y.to_index_map());
+        assert_eq!(sum, x.clone() + y.clone());
+        assert_eq!(
+            sum.to_index_map(),
+            BTreeMap::from([(100, 1), (4, 1), (0, 6), (-3, 3), (-50, 2)])
+        );
+        assert!(Tape::zero().to_index_map().is_empty());
+    }
+
+    #[test]
+    fn diff() {
+        let x = Tape::from_arrays([1, 0, 1], [1, 0, 0, 1]);
+        let y = Tape::from_arrays([2, 1, 0, 1], [1, 0, 1]);
+        assert_eq!(x.diff(&y), vec![(3, 0, 2), (-3, 0, 1), (-4, 1, 0)]);
+        assert_eq!(y.diff(&x), vec![(3, 2, 0), (-3, 1, 0), (-4, 0, 1)]);
+        assert!(x.diff(&x).is_empty());
+        assert!(x
+            .diff(&Tape::from_arrays([0, 1, 0, 1], [1, 0, 0, 1, 0]))
+            .is_empty());
+    }
+
+    #[test]
+    fn distance() {
+        let x = Tape::from_arrays([1, 0, 1], [1, 0, 0, 1]);
+        let y = Tape::from_arrays([2, 1, 0, 1], [1, 0, 3]);
+        assert_eq!(x.l1_distance(&y), 2 + 3 + 1);
+        assert_eq!(x.linf_distance(&y), 3);
+        assert_eq!(y.l1_distance(&x), x.l1_distance(&y));
+        assert_eq!(y.linf_distance(&x), x.linf_distance(&y));
+        assert_eq!(x.l1_distance(&x), 0);
+        assert_eq!(x.linf_distance(&x), 0);
+        assert_eq!(x.l1_distance(&Tape::zero()), x.digit_sum());
+        assert_eq!(Tape::zero().linf_distance(&Tape::zero()), 0);
+    }
+
+    #[test]
+    fn negative_period() {
+        assert_eq!(
+            Tape::from_arrays([1], [1, 0, 1, 0]).negative_period(),
+            Some(2)
+        );
+        assert_eq!(
+            Tape::from_arrays([], [1, 0, 1, 0, 1]).negative_period(),
+            Some(2)
+        );
+        assert_eq!(Tape::from_arrays([], [2, 2, 2]).negative_period(), Some(1));
+        assert_eq!(
+            Tape::from_arrays([], [1, 2, 3, 1, 2, 3]).negative_period(),
+            Some(3)
+        );
+        assert_eq!(
+            Tape::from_arrays([], [1, 2, 3, 1, 2]).negative_period(),
+            None
+        );
+        assert_eq!(Tape::from_arrays([], [1, 0, 0, 0]).negative_period(), None);
+        assert_eq!(Tape::from_arrays([1, 0, 1, 0], []).negative_period(), None);
+    }
+
+    #[test]
+    fn insert_digit() {
+        let rule = Rule::integer(10);
+        let mut tape = Tape::from_arrays([1, 2, 3], [4]);
+        let high = 100.;
+        let low = tape.value(&rule) - high;
+        tape.insert_digit(&rule, 1, 9);
+        assert_eq!(tape, Tape::from_arrays([1, 9, 2], [3, 4]));
+        assert_relative_eq!(tape.value(&rule), high + 9. * 10. + low / 10.);
+        tape.insert_digit(&rule, -5, 7);
+        assert_eq!(tape, Tape::from_arrays([1, 9, 2], [3, 4, 0, 0, 7]));
+        tape.insert_digit(&rule, 4, 5);
+        assert_eq!(tape, Tape::from_arrays([5, 0, 0, 1, 9], [2, 3, 4, 0, 0, 7]));
+    }
+
+    #[test]
+    fn checked_add() {
+        let x = Tape::from_arrays([1, 2], [3, 4, 5, 6]);
+        let y = Tape::from_arrays([1, 2, 3, 4], [5]);
+        assert_eq!(x.checked_add(&y), Some(x.clone() + y.clone()));
+        let big = Tape::from_arrays([Value::MAX - 1], [Value::MAX]);
+        assert_eq!(
+            big.checked_add(&Tape::from_arrays([1], [])),
+            Some(Tape::from_arrays([Value::MAX], [Value::MAX]))
+        );
+        assert_eq!(big.checked_add(&Tape::from_arrays([2], [])), None);
+        assert_eq!(big.checked_add(&Tape::from_arrays([], [1])), None);
+    }
+
+    #[test]
+    fn is_valid() {
+        let rule = Rule::from_array([1, 1]).unwrap();
+        assert!(!Tape::from_arrays([1, 2], [3, 4]).is_valid(&rule));
+        assert!(Tape::from_arrays([1, 1], [1, 1]).is_valid(&rule));
+        assert!(Tape::from_arrays([1, 0], [1, 0]).is_valid(&rule));
+        assert!(Tape::zero().is_valid(&rule));
+        let mut long = Tape::from_arrays([1; 200], [1; 200]);
+        assert!(long.is_valid(&rule));
+        long[-200] = 2;
+        assert!(!long.is_valid(&rule));
+        long[-200] = 0;
+        long[130] = 2;
+        assert!(!long.is_valid(&rule));
+    }
+
+    #[test]
+    fn validate() {
+        let rule = Rule::from_array([2, 1]).unwrap();
+        assert_eq!(Tape::from_arrays([2, 1], [0, 2]).validate(&rule), Ok(()));
+        assert_eq!(Tape::from_arrays([2, 3], [4, 2]).validate(&rule), Err(0));
+        assert_eq!(Tape::from_arrays([2, 1], [0, 3]).validate(&rule), Err(-2));
+        assert_eq!(Tape::zero().validate(&rule), Ok(()));
+        let (positives, negatives) = Tape::from_arrays([2, 1], [0, 3]).into_arrays();
+        assert_eq!(rule.check_digits(&positives), Ok(()));
+        assert_eq!(rule.check_digits(&negatives), Err(1));
+    }
+
+    #[test]
+    fn is_standard() {
         let rule = Rule::from_array([1, 1]).unwrap();
         assert!(!Tape::from_arrays([1, 1], [1, 1]).is_standard(&rule));
         assert!(Tape::from_arrays([1, 0], [1]).is_standard(&rule));
@@ -314,7 +2753,504 @@ mod test {
         assert_relative_eq!(result.value(&rule), 6.);
     }
 
+    #[test]
+    fn is_minimal_length() {
+        let rule = Rule::from_array([1, 1]).unwrap();
+        assert!(Tape::zero().is_minimal_length(&rule));
+        assert!(Tape::from_arrays([0, 0, 1, 0, 0], [0, 1]).is_minimal_length(&rule));
+        assert!(!Tape::from_arrays([0, 1, 1], []).is_minimal_length(&rule));
+        assert!(!Tape::from_arrays([1, 1], []).is_minimal_length(&rule));
+        assert!(Tape::from_arrays([1, 1, 1], []).is_minimal_length(&rule));
+        let rule = Rule::from_array([10]).unwrap();
+        assert!(!Tape::from_arrays([9, 10], []).is_minimal_length(&rule));
+    }
+
+    #[test]
+    fn describe() {
+        let rule = Rule::from_array([2]).unwrap();
+        let description = Tape::from_arrays([1, 0, 1], [1]).describe(&rule);
+        assert_eq!(description.lines().count(), 6);
+        for expected in [
+            "tape: 1 0 1,1",
+            "range: -1..3",
+            "value: 5.5",
+            "valid: true",
+            "standard: true",
+            "base: 2",
+        ] {
+            assert!(description.contains(expected), "{description}");
+        }
+        let description = Tape::from_arrays([3], []).describe(&rule);
+        assert!(description.contains("valid: false"));
+        assert!(description.contains("standard: false"));
+    }
+
+    #[test]
+    fn standardize_golden() {
+        // The single-pass standardization panicked on `1 1` in base phi.
+        let rule = Rule::from_array([1, 1]).unwrap();
+        let tape = Tape::from_arrays([1, 1], []);
+        let result = tape.standardize(&rule);
+        assert_eq!(result, Tape::from_arrays([1, 0, 0], []));
+        let tape = Tape::from_arrays([1, 0, 1, 1], [1, 1]);
+        let result = tape.standardize(&rule);
+        assert!(result.is_standard(&rule));
+        assert_relative_eq!(result.value(&rule), tape.value(&rule));
+    }
+
+    #[test]
+    fn eq_standard() {
+        let rule = Rule::golden();
+        let x = Tape::from_arrays([1, 0, 0], []);
+        let y = Tape::from_arrays([1, 1], []);
+        let z = Tape::from_arrays([1, 0], [1, 1]);
+        assert_ne!(x, y);
+        assert!(x.eq_standard(&y, &rule));
+        assert!(y.eq_standard(&z, &rule));
+        assert!(!x.eq_standard(&Tape::from_arrays([1, 0, 1], []), &rule));
+    }
+
+    #[test]
+    fn standardize_bounded() {
+        let rule = Rule::from_array([1, 1]).unwrap();
+        let tape = Tape::from_arrays([1; 8], [1; 4]);
+        let (result, finished) = tape.standardize_bounded(&rule, 1);
+        assert!(!finished);
+        assert!(!result.is_standard(&rule));
+        assert_relative_eq!(result.value(&rule), tape.value(&rule), max_relative = 1e-12);
+        let (result, finished) = tape.standardize_bounded(&rule, 1000);
+        assert!(finished);
+        assert!(result.is_standard(&rule));
+        assert_eq!(result, tape.standardize(&rule));
+        let standard = Tape::from_arrays([1, 0, 1], []);
+        assert_eq!(standard.standardize_bounded(&rule, 0), (standard, true));
+
+        // Both applications of a carry through a reverse application count against the budget.
+        let rule = Rule::from_array([2, 1, 1]).unwrap();
+        let tape = Tape::from_arrays([2, 2, 0], []);
+        assert_eq!(tape.carry_cost(&rule), 1);
+        assert_eq!(tape.application_cost(&rule), 2);
+        assert_eq!(tape.standardize_bounded(&rule, 1), (tape.clone(), false));
+        assert_eq!(
+            tape.standardize_bounded(&rule, 2),
+            (tape.standardize(&rule), true)
+        );
+    }
+
+    #[test]
+    fn add_digit_at() {
+        let rule = Rule::from_array([1, 1]).unwrap();
+        let mut tape = Tape::from_arrays([1, 0, 1], [0, 1]);
+        assert!(tape.is_standard(&rule));
+        let initial_value = tape.value(&rule);
+        tape.add_digit_at(&rule, 0, 100);
+        assert!(tape.is_standard(&rule));
+        assert!(tape.range().1 > 3);
+        assert_relative_eq!(
+            tape.value(&rule),
+            initial_value + 100.,
+            max_relative = 1e-12
+        );
+
+        let rule = Rule::from_array([3, 2, 1]).unwrap();
+        let mut tape = Tape::zero();
+        tape.add_digit_at(&rule, -1, 1000);
+        assert!(tape.is_standard(&rule));
+        assert_relative_eq!(tape.value(&rule), 1000. / rule.base(), max_relative = 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "The digit overflowed while adding to it.")]
+    fn add_digit_at_overflow() {
+        let rule = Rule::from_array([1, 1]).unwrap();
+        let mut tape = Tape::from_arrays([Value::MAX], []);
+        tape.add_digit_at(&rule, 0, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the zero tape is standard in base one.")]
+    fn add_digit_at_base_one() {
+        let rule = Rule::integer(1);
+        let mut tape = Tape::zero();
+        tape.add_digit_at(&rule, 0, 1);
+    }
+
+    #[test]
+    fn predict_apply_range() {
+        let rule = Rule::golden();
+        let tape = Tape::from_arrays([1, 1], []);
+        assert_eq!(tape.predict_apply_range(&rule, 2), (0, 3));
+        assert_eq!(tape.apply(&rule, 2).unwrap().range(), (0, 3));
+        let tape = Tape::from_arrays([], [0, 0, 0, 1, 1]);
+        assert_eq!(tape.predict_apply_range(&rule, -3), (-5, 0));
+        assert_eq!(tape.apply(&rule, -3).unwrap().range(), (-5, 0));
+        let tape = Tape::from_arrays([0, 1], [1]);
+        assert_eq!(tape.predict_apply_range(&rule, 1), (-1, 2));
+        assert_eq!(tape.apply(&rule, 1).unwrap().range(), (-1, 2));
+    }
+
+    #[test]
+    fn can_apply() {
+        let rule = Rule::from_array([2, 1]).unwrap();
+        let tape = Tape::from_arrays([0, 2, 1], [3]);
+        assert!(tape.can_apply(&rule, 2));
+        assert!(!tape.can_apply(&rule, 1));
+        assert!(!tape.can_apply(&rule, 0));
+        assert!(!tape.can_apply(&rule, 10));
+        assert!(Tape::from_arrays([2, 1], []).can_apply(&rule, 2));
+    }
+
+    #[test]
+    fn zero_out_digit() {
+        let rule = Rule::golden();
+        let mut tape = Tape::from_arrays([0, 3, 4], []);
+        let value = tape.value(&rule);
+        assert_eq!(tape.zero_out_digit(&rule, 1).unwrap(), 3);
+        assert_eq!(tape, Tape::from_arrays([3, 0, 1], []));
+        assert_relative_eq!(tape.value(&rule), value, max_relative = 1e-12);
+        assert_eq!(tape.zero_out_digit(&rule, 1).unwrap(), 0);
+
+        let rule = Rule::integer(10);
+        let mut tape = Tape::from_arrays([123], []);
+        assert_eq!(tape.zero_out_digit(&rule, 0).unwrap(), 12);
+        assert_eq!(tape, Tape::from_arrays([12, 3], []));
+
+        let rule = Rule::from_array([2, 1]).unwrap();
+        let mut tape = Tape::from_arrays([5, 1], []);
+        assert!(tape.zero_out_digit(&rule, 1).is_err());
+        assert_eq!(tape, Tape::from_arrays([1, 3, 0], []));
+        assert_relative_eq!(
+            tape.value(&rule),
+            Tape::from_arrays([5, 1], []).value(&rule),
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn apply_transaction() {
+        let rule = Rule::golden();
+        let tape = Tape::from_arrays([1, 1, 0, 1, 1], []);
+        let original = tape.clone();
+        let result = tape.apply_transaction(&rule, &[2, 5]).unwrap();
+        assert_eq!(result, Tape::from_arrays([1, 0, 0, 1, 0, 0], []));
+
+        let (step, error) = tape.apply_transaction(&rule, &[2, 5, 3, 0]).unwrap_err();
+        assert_eq!(step, 2);
+        assert!(matches!(
+            error,
+            ApplyRuleError::InsufficientDigit {
+                application_index: 3,
+                ..
+            }
+        ));
+        assert_eq!(tape, original);
+        assert_eq!(tape.positive_values, original.positive_values);
+        assert_eq!(tape.apply_transaction(&rule, &[]).unwrap(), tape);
+    }
+
+    #[test]
+    fn saturating_apply() {
+        let rule = Rule::from_array([3, 2, 1]).unwrap();
+        let tape = Tape::from_arrays([0, 4, 1], [0]);
+        let (result, deficit) = tape.saturating_apply(&rule, 2).unwrap();
+        assert_eq!(result, Tape::from_arrays([1, 1, 0], [0]));
+        assert_eq!(deficit, 1 + 1);
+        assert!(tape.apply(&rule, 2).is_err());
+
+        let tape = Tape::from_arrays([3, 2, 1], []);
+        let (result, deficit) = tape.saturating_apply(&rule, 3).unwrap();
+        assert_eq!(deficit, 0);
+        assert_eq!(result, tape.apply(&rule, 3).unwrap());
+
+        assert_eq!(
+            tape.saturating_apply(&rule, isize::MIN + 2).unwrap_err(),
+            IndexOverflow {
+                index: isize::MIN + 2,
+                offset: 3
+            }
+        );
+    }
+
+    #[test]
+    fn standardize_high_first() {
+        let rule = Rule::golden();
+        let tape = Tape::from_arrays([1, 1, 1, 1], []);
+        assert_eq!(
+            tape.standardize_high_first(&rule),
+            Tape::from_arrays([1, 0, 1, 0, 0], [])
+        );
+        // No direct application is possible, so this needs a reverse application first.
+        let rule = Rule::from_array([2, 1, 1]).unwrap();
+        let tape = Tape::from_arrays([2, 2], []);
+        assert!(tape.highest_applicable(&rule).is_none());
+        assert_eq!(tape.standardize_high_first(&rule), tape.standardize(&rule));
+        let rule = Rule::from_array([3, 2, 1]).unwrap();
+        let tape = Tape::from_arrays([3, 3, 3, 3, 3], [3, 3]);
+        assert_eq!(tape.standardize_high_first(&rule), tape.standardize(&rule));
+        assert_eq!(Tape::zero().standardize_high_first(&rule), Tape::zero());
+    }
+
+    #[test]
+    fn standardize_left_first() {
+        // The highest violation `2 2 0` needs a reverse application, while the rule applies
+        // directly further down, so the carry sequences start differently but end in the same tape.
+        let rule = Rule::from_array([2, 1, 1]).unwrap();
+        let tape = Tape::from_arrays([2, 2, 0, 2, 1, 1], []);
+        let mut indices = Vec::new();
+        let standard = tape.standardize_with_callback(&rule, |_, index| indices.push(index));
+        assert_eq!(indices[0], 6);
+        assert_eq!(tape.highest_applicable(&rule), Some(3));
+        assert_eq!(tape.standardize_left_first(&rule), standard);
+        let rule = Rule::golden();
+        let tape = Tape::from_arrays([1, 1, 0, 1, 1], [1]);
+        assert_eq!(tape.standardize_left_first(&rule), tape.standardize(&rule));
+    }
+
+    #[test]
+    fn apply_with_slice() {
+        let rule = Rule::from_array([2, 1]).unwrap();
+        let tape = Tape::from_arrays([0, 2, 1], [3]);
+        assert_eq!(
+            tape.clone().apply_with_slice(rule.values(), 2).unwrap(),
+            tape.apply(&rule, 2).unwrap()
+        );
+        let error = tape.clone().apply_with_slice(rule.values(), 1).unwrap_err();
+        assert!(matches!(
+            error,
+            ApplyRuleError::InsufficientDigit {
+                rule_index: 0,
+                tape_value: 1,
+                ..
+            }
+        ));
+        let error = tape.clone().apply_with_slice(rule.values(), 0).unwrap_err();
+        assert!(matches!(
+            error,
+            ApplyRuleError::InsufficientDigit {
+                rule_index: 1,
+                tape_value: 0,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn floor_and_ceil_standard() {
+        let rule = Rule::golden();
+        // 2 = phi + phi^-2 is not representable without fractional digits.
+        let tape = Tape::from_arrays([2], []);
+        assert_eq!(tape.floor_standard(&rule), Tape::from_arrays([1, 0], []));
+        assert_eq!(tape.ceil_standard(&rule), Tape::from_arrays([1, 0, 0], []));
+        let tape = Tape::from_arrays([2], [0, 0]);
+        assert_eq!(
+            tape.floor_standard(&rule),
+            Tape::from_arrays([1, 0], [0, 1])
+        );
+        assert_eq!(tape.ceil_standard(&rule), tape.floor_standard(&rule));
+
+        let standard = Tape::from_arrays([1, 0, 1], [0, 1]);
+        assert_eq!(standard.floor_standard(&rule), standard);
+        assert_eq!(standard.ceil_standard(&rule), standard);
+        assert_eq!(Tape::zero().ceil_standard(&rule), Tape::zero());
+    }
+
+    #[test]
+    fn canonicalize() {
+        let rule = Rule::golden();
+        let x = Tape::from_arrays([0, 0, 1, 1, 0], [0, 0]);
+        let y = Tape::from_arrays([0, 1, 0, 1], [1, 0]);
+        let x_canonical = x.canonicalize(&rule);
+        let y_canonical = y.canonicalize(&rule);
+        assert_eq!(x_canonical.positive_digits(), &[0, 0, 0, 1]);
+        assert!(x_canonical.negative_digits().is_empty());
+        assert_eq!(x_canonical.positive_digits(), y_canonical.positive_digits());
+        assert_eq!(x_canonical.negative_digits(), y_canonical.negative_digits());
+        assert_eq!(x_canonical.range(), y_canonical.range());
+        let zero = Tape::from_arrays([0, 0], [0]).canonicalize(&rule);
+        assert!(zero.positive_digits().is_empty() && zero.negative_digits().is_empty());
+    }
+
+    #[test]
+    fn tape_builder() {
+        let rule = Rule::golden();
+        let built = TapeBuilder::new(&rule)
+            .set(1, 1)
+            .set(0, 1)
+            .set(-1, 1)
+            .apply(2)
+            .standardize()
+            .build()
+            .unwrap();
+        let mut manual = Tape::zero();
+        manual[1] = 1;
+        manual[0] = 1;
+        manual[-1] = 1;
+        let manual = manual.apply(&rule, 2).unwrap().standardize(&rule);
+        assert_eq!(built, manual);
+
+        let error = TapeBuilder::from_tape(&rule, Tape::from_arrays([1], []))
+            .apply(2)
+            .set(0, 1)
+            .apply(5)
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            ApplyRuleError::InsufficientDigit {
+                application_index: 2,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn standardize_with_callback() {
+        let rule = Rule::golden();
+        let tape = Tape::from_arrays([1, 1, 1, 1], []);
+        let mut steps = Vec::new();
+        let result = tape.standardize_with_callback(&rule, |tape, index| {
+            steps.push((tape.clone(), index));
+        });
+        assert_eq!(result, tape.standardize(&rule));
+        assert_eq!(
+            steps,
+            vec![
+                (Tape::from_arrays([1, 1, 1, 1], []), 4),
+                (Tape::from_arrays([1, 0, 0, 1, 1], []), 2),
+            ]
+        );
+        assert_eq!(steps.len(), tape.carry_cost(&rule));
+
+        let rule = Rule::from_array([2, 1, 1]).unwrap();
+        for tape in [
+            Tape::from_arrays([2, 2], []),
+            Tape::from_arrays([2, 1, 2, 2, 0, 1], [2, 2]),
+        ] {
+            let mut count = 0;
+            let result = tape.standardize_with_callback(&rule, |_, _| count += 1);
+            assert_eq!(result, tape.standardize(&rule));
+            assert_eq!(count, tape.carry_cost(&rule));
+        }
+    }
+
+    #[test]
+    fn carry_cost() {
+        let rule = Rule::golden();
+        assert_eq!(Tape::zero().carry_cost(&rule), 0);
+        let tape = Tape::from_arrays([1, 1], []);
+        assert_eq!(tape.carry_cost(&rule), 1);
+
+        let rule = Rule::integer(2);
+        let tape = Tape::from_arrays([1, 1, 1, 1, 1, 1, 2], []);
+        assert_eq!(tape.carry_cost(&rule), 7);
+        assert_eq!(tape.application_cost(&rule), 7);
+        let (standard, is_standard) = tape.standardize_bounded(&rule, 7);
+        assert!(is_standard);
+        assert_eq!(standard, tape.standardize(&rule));
+        assert!(!tape.standardize_bounded(&rule, 6).1);
+    }
+
+    /// Rules with up to `max_len` non-increasing values between 1 and `max_value`.
+    fn rule(max_value: Value, max_len: usize) -> impl Strategy<Value = Rule> {
+        proptest::collection::vec(1..=max_value, 1..=max_len).prop_map(|mut values| {
+            values.sort_unstable_by(|a, b| b.cmp(a));
+            Rule::from_array(values).unwrap()
+        })
+    }
+
+    /// Rules with base greater than one together with a tape that is valid for them, with fewer
+    /// than `max_tape_len` digits on each side of the comma.
+    fn rule_and_tape(
+        max_rule_len: usize,
+        max_tape_len: usize,
+    ) -> impl Strategy<Value = (Rule, Tape)> {
+        rule(10, max_rule_len)
+            .prop_filter("base one", |rule| rule.base() > 1.)
+            .prop_flat_map(move |rule| {
+                let digits = proptest::collection::vec(0..=rule.first(), 0..max_tape_len);
+                (Just(rule), digits.clone(), digits)
+            })
+            .prop_map(|(rule, positives, negatives)| {
+                (rule, Tape::from_arrays(positives, negatives))
+            })
+    }
+
     proptest! {
+        #[test]
+        fn is_valid_matches_digit_scan(tape_negatives in proptest::collection::vec(0u32..=10, 0..150),
+                tape_positives in proptest::collection::vec(0u32..=10, 0..150),
+                first in 1u32..=10) {
+            let tape = Tape::from_arrays(tape_positives, tape_negatives);
+            let rule = Rule::integer(first);
+            prop_assert_eq!(tape.is_valid(&rule), tape.iter().all(|value| value <= first));
+        }
+
+        #[test]
+        fn application_cost_budget((rule, tape) in rule_and_tape(5, 15)) {
+            let carries = tape.carry_cost(&rule);
+            let applications = tape.application_cost(&rule);
+            prop_assert!(carries <= applications && applications <= 2 * carries);
+            prop_assert!(tape.standardize_bounded(&rule, applications).1);
+        }
+
+        #[test]
+        fn from_balanced_value(positives in proptest::collection::vec(-2i64..=4, 0..8),
+                negatives in proptest::collection::vec(-3i64..=3, 0..8),
+                rule in rule(4, 4)) {
+            prop_assume!(rule.base() > 1.);
+            let value: f64 = positives.iter().rev().enumerate()
+                .map(|(i, &digit)| digit as f64 * rule.base().powi(i as i32))
+                .chain(negatives.iter().enumerate().map(|(i, &digit)| digit as f64 * rule.base().powi(-(i as i32) - 1)))
+                .sum();
+            prop_assume!(value > 1e-6);
+            let tape = Tape::from_balanced(&positives, &negatives, &rule);
+            prop_assert!(tape.is_standard(&rule));
+            assert_relative_eq!(tape.value(&rule), value, max_relative = 1e-9);
+        }
+
+        #[test]
+        fn standardize_high_first_value((rule, tape) in rule_and_tape(4, 10)) {
+            let result = tape.standardize_high_first(&rule);
+            prop_assert!(result.is_standard(&rule));
+            assert_relative_eq!(result.value(&rule), tape.value(&rule), max_relative = 1e-12);
+            prop_assert_eq!(result, tape.standardize(&rule));
+        }
+
+        #[test]
+        fn standardize_left_first_value((rule, tape) in rule_and_tape(4, 10)) {
+            let result = tape.standardize_left_first(&rule);
+            prop_assert!(result.is_standard(&rule));
+            assert_relative_eq!(result.value(&rule), tape.value(&rule), max_relative = 1e-12);
+            prop_assert_eq!(result, tape.standardize(&rule));
+        }
+
+        #[test]
+        fn floor_and_ceil_standard_bracket(tape_negatives in proptest::collection::vec(0u32..=10, 0..6),
+                tape_positives in proptest::collection::vec(0u32..=10, 0..6),
+                rule in rule(5, 3)) {
+            prop_assume!(rule.base() > 1.);
+            let tape = Tape::from_arrays(tape_positives, tape_negatives);
+            let floor = tape.floor_standard(&rule);
+            let ceil = tape.ceil_standard(&rule);
+            prop_assert!(floor.is_standard(&rule));
+            prop_assert!(ceil.is_standard(&rule));
+            for rounded in [&floor, &ceil] {
+                prop_assert!(rounded.nonzero_range().is_none_or(|(low, _)| low >= tape.range().0));
+            }
+            let value = tape.value(&rule);
+            prop_assert!(floor.value(&rule) <= value * (1. + 1e-12));
+            prop_assert!(value <= ceil.value(&rule) * (1. + 1e-12));
+        }
+
+        #[test]
+        fn can_apply_matches_apply(tape_negatives in proptest::collection::vec(0u32..=4, 0..8),
+                tape_positives in proptest::collection::vec(0u32..=4, 0..8),
+                rule in rule(4, 3),
+                index in -10isize..10) {
+            let tape = Tape::from_arrays(tape_positives, tape_negatives);
+            prop_assert_eq!(tape.can_apply(&rule, index), tape.apply(&rule, index).is_ok());
+        }
+
         #[test]
         fn apply_rule(tape_negatives in proptest::collection::vec(0u32..=100, 0..10),
                 tape_positives in proptest::collection::vec(0u32..=100, 0..10),
@@ -325,7 +3261,20 @@ mod test {
                 if let Ok(result) = tape.apply(&rule, index) {
                     let tape_value = tape.value(&rule);
                     let result_value = result.value(&rule);
-                    assert_relative_eq!(result_value, tape_value);
+                    assert_relative_eq!(result_value, tape_value, max_relative = 1e-12);
+                }
+            }
+        }
+
+        #[test]
+        fn predict_apply_range_matches(tape_negatives in proptest::collection::vec(0u32..=10, 0..10),
+                tape_positives in proptest::collection::vec(0u32..=10, 0..10),
+                rule_values in proptest::collection::vec(1u32..=3, 1..5),
+                index in -15isize..15) {
+            let tape = Tape::from_arrays(tape_positives, tape_negatives);
+            if let Some(rule) = Rule::from_array(rule_values) {
+                if let Ok(result) = tape.apply(&rule, index) {
+                    prop_assert_eq!(tape.predict_apply_range(&rule, index), result.range());
                 }
             }
         }
@@ -341,7 +3290,50 @@ mod test {
                 let result = tape.standardize(&rule);
                 let tape_value = tape.value(&rule);
                 let result_value = result.value(&rule);
-                assert_relative_eq!(result_value, tape_value);
+                assert_relative_eq!(result_value, tape_value, max_relative = 1e-12);
+                prop_assert!(result.is_standard(&rule));
+            }
+        }
+
+        #[test]
+        fn standardize_valid(tape_negatives in proptest::collection::vec(0u32..=10, 0..10),
+                tape_positives in proptest::collection::vec(0u32..=10, 0..10),
+                rule_values in proptest::collection::vec(1u32..=10, 1..5)) {
+            if let Some(rule) = Rule::from_array(rule_values) {
+                prop_assume!(rule.base() > 1.);
+                let max = rule.first();
+                let tape_negatives: Vec<_> = tape_negatives.iter().map(|&x| x % (max + 1)).collect();
+                let tape_positives: Vec<_> = tape_positives.iter().map(|&x| x % (max + 1)).collect();
+                let tape = Tape::from_arrays(tape_positives, tape_negatives);
+                let result = tape.standardize(&rule);
+                assert_relative_eq!(result.value(&rule), tape.value(&rule), max_relative = 1e-9);
+                prop_assert!(result.is_standard(&rule));
+            }
+        }
+
+        #[test]
+        fn from_f64_value(value in 0f64..1000., rule_values in proptest::collection::vec(1u32..=10, 1..5)) {
+            if let Some(rule) = Rule::from_array(rule_values) {
+                prop_assume!(rule.base() > 1.);
+                let tape = Tape::from_f64(value, &rule, 60);
+                prop_assert!(tape.is_standard(&rule));
+                assert_relative_eq!(tape.value(&rule), value, epsilon = 1e-9, max_relative = 1e-9);
+            }
+        }
+
+        #[test]
+        fn add_digit_at_value(tape_negatives in proptest::collection::vec(0u32..=10, 0..10),
+                tape_positives in proptest::collection::vec(0u32..=10, 0..10),
+                rule_values in proptest::collection::vec(1u32..=10, 1..5),
+                index in -10isize..10,
+                amount in 0u32..=100) {
+            if let Some(rule) = Rule::from_array(rule_values) {
+                prop_assume!(rule.base() > 1.);
+                let mut tape = Tape::from_arrays(tape_positives, tape_negatives);
+                let expected = tape.value(&rule) + f64::from(amount) * rule.base().powi(i32::try_from(index).unwrap());
+                tape.add_digit_at(&rule, index, amount);
+                assert_relative_eq!(tape.value(&rule), expected, max_relative = 1e-9);
+                prop_assert!(tape.is_standard(&rule));
             }
         }
     }